@@ -0,0 +1,133 @@
+use std::fmt;
+
+// ParseError is a puzzle input that couldn't be parsed. It says where in the input things went
+// wrong, counting lines and columns from 1, and what was there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, message: &str) -> ParseError {
+        ParseError {
+            file: None,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    // at works out the line and column of text from where it sits in contents, so text has to be
+    // a slice of contents. Anything else is put at the end of contents.
+    pub fn at(contents: &str, text: &str, message: &str) -> ParseError {
+        let start = contents.as_ptr() as usize;
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|o| *o <= contents.len())
+            .unwrap_or(contents.len());
+
+        let before = &contents[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+        ParseError::new(line, column, text, message)
+    }
+
+    // in_file says which file the input came from, which the parsers themselves never know.
+    pub fn in_file(mut self, file: &str) -> ParseError {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file, self.line, self.column)?,
+            None => write!(f, "line {}, column {}", self.line, self.column)?,
+        }
+        write!(f, ": {}, found {:?}", self.message, self.text)
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(err: ParseError) -> String {
+        err.to_string()
+    }
+}
+
+// Solver is one day of advent of code. The puzzle input is parsed once, and both parts are
+// answered from the parsed input. Answers are strings since every day's answer is a different
+// kind of number, and some of them are too big for an i64.
+pub trait Solver {
+    // Input is whatever parse turns the puzzle input into. It can borrow from the puzzle input.
+    type Input<'a>;
+
+    fn parse(contents: &str) -> Result<Self::Input<'_>, ParseError>;
+    fn part1(input: &Self::Input<'_>) -> Result<String, String>;
+    fn part2(input: &Self::Input<'_>) -> Result<String, String>;
+}
+
+// part1 parses contents and answers part 1, for when there's only one part to answer.
+pub fn part1<S: Solver>(contents: &str) -> Result<String, String> {
+    S::part1(&S::parse(contents)?)
+}
+
+// part2 parses contents and answers part 2.
+pub fn part2<S: Solver>(contents: &str) -> Result<String, String> {
+    S::part2(&S::parse(contents)?)
+}
+
+#[cfg(test)]
+struct Sum;
+
+#[cfg(test)]
+impl Solver for Sum {
+    type Input<'a> = Vec<&'a str>;
+
+    fn parse(contents: &str) -> Result<Vec<&str>, ParseError> {
+        Ok(contents.lines().collect())
+    }
+
+    fn part1(input: &Vec<&str>) -> Result<String, String> {
+        Ok(input.len().to_string())
+    }
+
+    fn part2(input: &Vec<&str>) -> Result<String, String> {
+        let mut sum = 0;
+        for line in input {
+            sum += line.parse::<i32>().map_err(|_| format!("Could not parse {}", line))?;
+        }
+        Ok(sum.to_string())
+    }
+}
+
+#[test]
+fn test_parts() {
+    assert_eq!(Ok("3".to_string()), part1::<Sum>("1\n2\n3\n"));
+    assert_eq!(Ok("6".to_string()), part2::<Sum>("1\n2\n3\n"));
+    assert_eq!(Err("Could not parse x".to_string()), part2::<Sum>("1\nx\n"));
+}
+
+#[test]
+fn test_parse_error_at() {
+    let contents = "12\n3x4\n";
+    let err = ParseError::at(contents, &contents[4..5], "expected a digit");
+    assert_eq!(ParseError::new(2, 2, "x", "expected a digit"), err);
+    assert_eq!(
+        "line 2, column 2: expected a digit, found \"x\"",
+        err.to_string()
+    );
+    assert_eq!(
+        "input.txt:2:2: expected a digit, found \"x\"",
+        err.in_file("input.txt").to_string()
+    );
+
+    let err = ParseError::at(contents, "", "expected a number");
+    assert_eq!((3, 1), (err.line, err.column));
+}
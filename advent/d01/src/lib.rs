@@ -0,0 +1,76 @@
+use common::ParseError;
+
+// get_lines parses the expense report, which is one number per line.
+pub fn get_lines(contents: &str) -> Result<Vec<i32>, ParseError> {
+    return contents.lines()
+            .map(|j|{j.parse::<i32>().map_err(|_| ParseError::at(contents, j, "Could not parse number"))})
+            .collect()
+}
+
+// Day01 is the expense report.
+pub struct Day01;
+
+impl common::Solver for Day01 {
+    type Input<'a> = Vec<i32>;
+
+    fn parse(contents: &str) -> Result<Vec<i32>, ParseError> {
+        get_lines(contents)
+    }
+
+    // part1 finds the two entries that sum to 2020 and multiplies them together.
+    fn part1(lines: &Vec<i32>) -> Result<String, String> {
+        for (x, i) in lines.iter().enumerate() {
+            for j in lines.iter().skip(x + 1) {
+                if i + j == 2020 {
+                    return Ok((i * j).to_string());
+                }
+            }
+        }
+
+        return Err("No two entries sum to 2020".to_string());
+    }
+
+    // part2 is part1, but with three entries.
+    fn part2(lines: &Vec<i32>) -> Result<String, String> {
+        for (x, i) in lines.iter().enumerate() {
+            for (y, j) in lines.iter().enumerate().skip(x + 1) {
+                for k in lines.iter().skip(y + 1) {
+                    if i + j + k == 2020 {
+                        return Ok((i * j * k).to_string());
+                    }
+                }
+            }
+        }
+
+        return Err("No three entries sum to 2020".to_string());
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day01>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day01>(contents)
+}
+
+#[test]
+fn test_expense_report() {
+    let contents = "\
+1721
+979
+366
+299
+675
+1456";
+    assert_eq!(Ok("514579".to_string()), part1(contents));
+    assert_eq!(Ok("241861950".to_string()), part2(contents));
+}
+
+#[test]
+fn test_bad_expense_report() {
+    assert_eq!(
+        Err(ParseError::new(2, 1, "97x", "Could not parse number")),
+        get_lines("1721\n97x\n366")
+    );
+}
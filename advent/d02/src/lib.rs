@@ -0,0 +1,165 @@
+use common::ParseError;
+use regex::Regex;
+
+pub struct Solver {}
+
+impl Solver {
+    // solve is solve_contents for the contents of filename, so a ParseError says which file it's
+    // in. Reading the file and printing the error are up to the caller.
+    pub fn solve(filename: &str, contents: String) -> Result<i32, ParseError> {
+        Solver::solve_contents(contents).map_err(|e| e.in_file(filename))
+    }
+
+    // solve_contents counts passwords where exactly one of the two positions holds the letter.
+    pub fn solve_contents(contents: String) -> Result<i32, ParseError> {
+        return Ok(Solver::count_valid(&Solver::parse(&contents)?, PasswordPolicy::correct));
+    }
+
+    // solve_contents_count counts passwords where the letter shows up between lower and upper times.
+    pub fn solve_contents_count(contents: String) -> Result<i32, ParseError> {
+        return Ok(Solver::count_valid(&Solver::parse(&contents)?, PasswordPolicy::correct_count));
+    }
+
+    // parse reads the password list, which is a policy and a password per line.
+    pub fn parse(contents: &str) -> Result<Vec<(PasswordPolicy, &str)>, ParseError> {
+        let matcher = Regex::new(r"^(\d+)-(\d+) (\w): (\w+)$").unwrap();
+        let lines = contents.lines();
+
+        let mut passwords = Vec::new();
+        for line in lines {
+            let caps = matcher.captures(line)
+                .ok_or_else(|| ParseError::at(contents, line, "Not a <lower>-<upper> <letter>: <password> line"))?;
+            let number = |i: usize| {
+                let m = caps.get(i).unwrap().as_str();
+                m.parse::<i32>().map_err(|_| ParseError::at(contents, m, "Number is too big"))
+            };
+            let lower: i32 = number(1)?;
+            let upper: i32 = number(2)?;
+            let letter = caps.get(3).unwrap().as_str();
+            let content = caps.get(4).unwrap().as_str();
+
+            passwords.push((PasswordPolicy::new(letter.to_string(), lower, upper), content));
+        }
+
+        return Ok(passwords);
+    }
+
+    fn count_valid(passwords: &[(PasswordPolicy, &str)], valid: fn(&PasswordPolicy, &str) -> bool) -> i32 {
+        let mut valid_pws = 0;
+        for (pw_policy, content) in passwords {
+            if valid(pw_policy, content) {
+                valid_pws+=1;
+            }
+        }
+
+        return valid_pws;
+    }
+}
+
+// Day02 is the password list, checked by Solver.
+pub struct Day02;
+
+impl common::Solver for Day02 {
+    type Input<'a> = Vec<(PasswordPolicy, &'a str)>;
+
+    fn parse(contents: &str) -> Result<Vec<(PasswordPolicy, &str)>, ParseError> {
+        Solver::parse(contents)
+    }
+
+    fn part1(passwords: &Vec<(PasswordPolicy, &str)>) -> Result<String, String> {
+        Ok(Solver::count_valid(passwords, PasswordPolicy::correct_count).to_string())
+    }
+
+    fn part2(passwords: &Vec<(PasswordPolicy, &str)>) -> Result<String, String> {
+        Ok(Solver::count_valid(passwords, PasswordPolicy::correct).to_string())
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day02>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day02>(contents)
+}
+
+#[derive(Debug)]
+pub struct PasswordPolicy {
+    letter: char,
+    lower: i32,
+    upper: i32,
+}
+
+impl PasswordPolicy {
+    pub fn new(letter: String, lower: i32, upper: i32) -> PasswordPolicy {
+        let letter: char = letter.chars().next().unwrap();
+        return PasswordPolicy{letter, lower, upper};
+    }
+
+    pub fn correct(&self, content: &str) -> bool {
+        let mut count = 0;
+        let mut seen = false;
+        for (position, c) in content.chars().enumerate().map(PasswordPolicy::one_indexed) {
+            let p: i32 = position as i32;
+
+            if c == self.letter && (p == self.lower || p == self.upper){
+                count += 1;
+                if seen {
+                    return false
+                }
+                seen = true;    
+            }
+        }
+        
+        return count > 0;
+    }
+
+    pub fn correct_count(&self, content: &str) -> bool {
+        let count = content.chars().filter(|c| *c == self.letter).count() as i32;
+        return count >= self.lower && count <= self.upper;
+    }
+
+
+    fn one_indexed<T>((n, x): (usize, T)) -> (usize, T) {
+        (n+1, x)
+    }
+}
+
+#[test]
+fn two_solutions() {
+    let contents = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+    let result = Solver::solve_contents(contents.to_string());
+    assert_eq!(Ok(1), result)
+}
+
+#[test]
+fn count_solutions() {
+    let contents = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+    let result = Solver::solve_contents_count(contents.to_string());
+    assert_eq!(Ok(2), result)
+}
+
+#[test]
+fn bad_policy() {
+    let contents = "\
+1-3 a: abcde
+1-3 b cdefg";
+    assert_eq!(
+        Err(ParseError::new(2, 1, "1-3 b cdefg", "Not a <lower>-<upper> <letter>: <password> line")),
+        Solver::solve_contents(contents.to_string())
+    );
+    assert_eq!(
+        Err(ParseError::new(1, 3, "99999999999", "Number is too big")),
+        Solver::solve_contents("1-99999999999 a: abcde".to_string())
+    );
+    assert_eq!(
+        Err(ParseError::new(2, 1, "1-3 b cdefg", "Not a <lower>-<upper> <letter>: <password> line").in_file("passwords.txt")),
+        Solver::solve("passwords.txt", contents.to_string())
+    );
+}
@@ -0,0 +1,212 @@
+use common::ParseError;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Space {
+    Clear,
+    Tree,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Direction {
+    pub right: i32,
+    pub down: i32,
+}
+
+impl Space {
+    // parses a space in the board game, which looks like..
+    // ..##....... <<< where . is a clearing and # is a tree.
+    // we don't allow boards that have any other symbol.
+    pub fn parse(x: char) -> Result<Space, &'static str> {
+        match x {
+            '#' => return Ok(Space::Tree),
+            '.' => return Ok(Space::Clear),
+            _ => return Err("Not a parsable character"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct InfiniteRow {
+    columns: Vec<Space>,
+}
+
+impl InfiniteRow {
+    pub fn new(spaces: Vec<Space>) -> InfiniteRow {
+        return InfiniteRow { columns: spaces };
+    }
+
+    pub fn get(&self, i: i32) -> Space {
+        let board_space: usize = i as usize % self.columns.len();
+        return self.columns[board_space].clone();
+    }
+}
+
+#[test]
+fn check_infinite_row_continues_forever() {
+    let contents = vec![Space::Tree, Space::Clear, Space::Clear];
+    let row = InfiniteRow::new(contents);
+
+    assert_eq!(row.get(0), Space::Tree);
+    assert_eq!(row.get(3), Space::Tree);
+    assert_eq!(row.get(4), Space::Clear);
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct InfiniteBoard {
+    rows: Vec<InfiniteRow>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObservedPath {
+    pub observed: Vec<Space>,
+}
+
+impl InfiniteBoard {
+    pub fn new(contents: String) -> Result<InfiniteBoard, ParseError> {
+        let mut all_rows = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            let mut row = Vec::new();
+            for (i, c) in line.char_indices() {
+                let space = match Space::parse(c) {
+                    Ok(s) => s,
+                    Err(e) => return Err(ParseError::at(&contents, &line[i..i + c.len_utf8()], e)),
+                };
+
+                row.push(space);
+            }
+
+            // An empty row would leave the toboggan with nowhere to go.
+            if row.is_empty() {
+                return Err(ParseError::at(&contents, line, "Empty row"));
+            }
+
+            all_rows.push(InfiniteRow::new(row));
+        }
+
+        return Ok(InfiniteBoard { rows: all_rows });
+    }
+
+    pub fn traverse(&self, direction: Direction) -> ObservedPath {
+        let mut current_row: i32 = 0;
+        let mut current_column: i32 = 0;
+        let mut observed: Vec<Space> = Vec::new();
+
+        loop {
+            current_row += direction.down;
+            current_column += direction.right;
+
+            if current_row as usize >= self.rows.len() {
+                break;
+            }
+
+            let row: &InfiniteRow = self.rows.get(current_row as usize).unwrap();
+
+            let space = row.get(current_column);
+            observed.push(space);
+        }
+
+        let op = ObservedPath { observed: observed };
+        return op;
+    }
+}
+
+#[test]
+fn test_traverse() {
+    let contents = "\
+.#
+..
+##";
+    let board = InfiniteBoard::new(contents.to_owned()).unwrap();
+    let path = board.traverse(Direction { right: 1, down: 1 });
+    assert_eq!(vec!(Space::Clear, Space::Tree), path.observed)
+}
+
+#[test]
+fn test_bad_board() {
+    let contents = "\
+.#
+.x
+##";
+    assert_eq!(
+        Err(ParseError::new(2, 2, "x", "Not a parsable character")),
+        InfiniteBoard::new(contents.to_owned())
+    );
+    assert_eq!(
+        Err(ParseError::new(2, 1, "", "Empty row")),
+        InfiniteBoard::new(".#\n\n##".to_owned())
+    );
+}
+
+impl ObservedPath {
+    pub fn trees(&self) -> i64 {
+        let mut tree_accumulator = 0;
+        for space in self.observed.iter() {
+            match space {
+                Space::Tree => tree_accumulator += 1,
+                _ => {}
+            };
+        }
+        return tree_accumulator;
+    }
+}
+
+// Day03 is the toboggan ride through the trees.
+pub struct Day03;
+
+impl common::Solver for Day03 {
+    type Input<'a> = InfiniteBoard;
+
+    fn parse(contents: &str) -> Result<InfiniteBoard, ParseError> {
+        InfiniteBoard::new(contents.to_string())
+    }
+
+    // part1 counts the trees hit going right 3, down 1.
+    fn part1(board: &InfiniteBoard) -> Result<String, String> {
+        let path = board.traverse(Direction { right: 3, down: 1 });
+        return Ok(path.trees().to_string());
+    }
+
+    // part2: Using a bunch of random directions, gather all the tree "summations" and multiply them together
+    fn part2(board: &InfiniteBoard) -> Result<String, String> {
+        let all_puzzles = vec![
+            Direction { right: 1, down: 1 },
+            Direction { right: 3, down: 1 },
+            Direction { right: 5, down: 1 },
+            Direction { right: 7, down: 1 },
+            Direction { right: 1, down: 2 },
+        ];
+
+        let mut mult_accumulator = 1;
+        for puzzle in all_puzzles {
+            mult_accumulator *= board.traverse(puzzle).trees();
+        }
+        return Ok(mult_accumulator.to_string());
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day03>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day03>(contents)
+}
+
+#[test]
+fn test_slopes() {
+    let contents = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+    assert_eq!(Ok("7".to_string()), part1(contents));
+    assert_eq!(Ok("336".to_string()), part2(contents));
+}
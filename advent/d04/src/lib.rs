@@ -0,0 +1,173 @@
+use common::ParseError;
+use schema::{parse_records, Record, Schema, Violation};
+
+pub mod report;
+pub mod schema;
+
+// Reason is why a passport is invalid, for counting them up in a report. Every way of breaking the
+// schema maps to one.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Reason {
+    BIRTH_INVALID,
+    ISSUE_INVALID,
+    EXPIRATION_INVALID,
+    HEIGHT_INVALID,
+    HAIR_INVALID,
+    EYE_INVALID,
+    ID_INVALID,
+    MISSING_FIELDS,
+    UNKNOWN_FIELDS,
+    REPEATED_FIELDS,
+    // A field the passport rules don't have breaks a rule of its own.
+    FIELD_INVALID,
+}
+
+impl Reason {
+    // ALL is every reason, in the order reports list them.
+    pub const ALL: [Reason; 11] = [
+        Reason::MISSING_FIELDS,
+        Reason::BIRTH_INVALID,
+        Reason::ISSUE_INVALID,
+        Reason::EXPIRATION_INVALID,
+        Reason::EYE_INVALID,
+        Reason::ID_INVALID,
+        Reason::HAIR_INVALID,
+        Reason::HEIGHT_INVALID,
+        Reason::UNKNOWN_FIELDS,
+        Reason::REPEATED_FIELDS,
+        Reason::FIELD_INVALID,
+    ];
+
+    // of is the reason a record is invalid when it breaks its schema with violation.
+    pub fn of(violation: &Violation) -> Reason {
+        match violation {
+            Violation::Missing(_) => Reason::MISSING_FIELDS,
+            Violation::Repeated(_) => Reason::REPEATED_FIELDS,
+            Violation::Unknown(_) => Reason::UNKNOWN_FIELDS,
+            Violation::Invalid { key, .. } => match key.as_str() {
+                "byr" => Reason::BIRTH_INVALID,
+                "iyr" => Reason::ISSUE_INVALID,
+                "eyr" => Reason::EXPIRATION_INVALID,
+                "hgt" => Reason::HEIGHT_INVALID,
+                "hcl" => Reason::HAIR_INVALID,
+                "ecl" => Reason::EYE_INVALID,
+                "pid" => Reason::ID_INVALID,
+                _ => Reason::FIELD_INVALID,
+            },
+        }
+    }
+}
+
+// solve counts the passports in contents that the puzzle's schema accepts. A key the schema doesn't
+// know makes that passport invalid rather than the whole batch unreadable.
+pub fn solve(contents: &str) -> Result<i32, ParseError> {
+    solve_with(&Schema::passport(), contents)
+}
+
+// solve_with counts the records in contents that schema accepts.
+pub fn solve_with(schema: &Schema, contents: &str) -> Result<i32, ParseError> {
+    Ok(count_valid(schema, &parse_records(contents)?))
+}
+
+fn count_valid(schema: &Schema, records: &[Record]) -> i32 {
+    records
+        .iter()
+        .filter(|record| schema.validate(record).is_empty())
+        .count() as i32
+}
+
+// Day04 is the passport scanner, checking passports against the puzzle's schema.
+pub struct Day04;
+
+impl common::Solver for Day04 {
+    type Input<'a> = Vec<Record>;
+
+    fn parse(contents: &str) -> Result<Vec<Record>, ParseError> {
+        parse_records(contents)
+    }
+
+    // part1 only cares that every required field is there, not what's in them.
+    fn part1(records: &Vec<Record>) -> Result<String, String> {
+        let schema = Schema::passport();
+        let complete = records
+            .iter()
+            .filter(|record| {
+                !schema
+                    .validate(record)
+                    .iter()
+                    .any(|v| matches!(v, Violation::Missing(_)))
+            })
+            .count();
+        Ok(complete.to_string())
+    }
+
+    fn part2(records: &Vec<Record>) -> Result<String, String> {
+        Ok(count_valid(&Schema::passport(), records).to_string())
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day04>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day04>(contents)
+}
+
+#[test]
+fn test_content_parsing() {
+    let contents = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in";
+
+    assert_eq!(Ok("2".to_string()), part1(&contents));
+    assert_eq!(Ok("2".to_string()), part2(&contents));
+    assert_eq!(Ok(2), solve(contents));
+}
+
+#[test]
+fn test_unknown_fields() {
+    // An unknown key is complete enough for part 1 but breaks the strict schema for part 2, and
+    // doesn't stop the rest of the batch being counted.
+    let contents = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd pin:1234
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm";
+    assert_eq!(Ok("2".to_string()), part1(contents));
+    assert_eq!(Ok("1".to_string()), part2(contents));
+    assert_eq!(Ok(1), solve(contents));
+
+    let lenient = Schema::parse(&schema::PASSPORT_SCHEMA.replace("strict", "")).unwrap();
+    assert_eq!(Ok(2), solve_with(&lenient, contents));
+}
+
+#[test]
+fn test_bad_passports() {
+    let contents = "\
+ecl:gry pid:860033327
+
+iyr:2013 ecl:amb
+hcl:#cfa07d byr1929";
+    assert_eq!(
+        Err(ParseError::new(
+            4,
+            13,
+            "byr1929",
+            "Not properly formatted key-value pairs"
+        )),
+        solve(contents)
+    );
+}
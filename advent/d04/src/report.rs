@@ -0,0 +1,327 @@
+use crate::schema::{parse_records, Schema, Violation};
+use crate::Reason;
+use common::ParseError;
+use std::fmt::Write;
+
+// Outcome is what the scanner made of one passport in a batch.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Outcome {
+    // Which passport it is, counting from 1.
+    pub passport: usize,
+    // The line the passport starts on.
+    pub line: usize,
+    // Every reason the passport is invalid, in the order of Reason::ALL, so nothing means it's
+    // valid.
+    pub reasons: Vec<Reason>,
+    // Every rule the passport breaks, in the order the schema lists them.
+    pub violations: Vec<Violation>,
+}
+
+impl Outcome {
+    pub fn is_valid(&self) -> bool {
+        self.reasons.is_empty()
+    }
+}
+
+// Report is the outcome for every passport in a batch, checked against a schema the same way
+// solve_with counts them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Report {
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    // new checks contents against the puzzle's schema.
+    pub fn new(contents: &str) -> Result<Report, ParseError> {
+        Report::with_schema(&Schema::passport(), contents)
+    }
+
+    pub fn with_schema(schema: &Schema, contents: &str) -> Result<Report, ParseError> {
+        let records = parse_records(contents)?;
+        let outcomes = records
+            .iter()
+            .zip(start_lines(contents))
+            .enumerate()
+            .map(|(i, (record, line))| {
+                let violations = schema.validate(record);
+                let found: Vec<Reason> = violations.iter().map(Reason::of).collect();
+                Outcome {
+                    passport: i + 1,
+                    line,
+                    reasons: Reason::ALL
+                        .iter()
+                        .filter(|reason| found.contains(reason))
+                        .copied()
+                        .collect(),
+                    violations,
+                }
+            })
+            .collect();
+        Ok(Report { outcomes })
+    }
+
+    pub fn valid(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.is_valid()).count()
+    }
+
+    // summary is how many passports fail for each reason, for every reason. A passport that fails
+    // for more than one reason counts towards each of them.
+    pub fn summary(&self) -> Vec<(Reason, usize)> {
+        Reason::ALL
+            .iter()
+            .map(|reason| {
+                let count = self
+                    .outcomes
+                    .iter()
+                    .filter(|o| o.reasons.contains(reason))
+                    .count();
+                (*reason, count)
+            })
+            .collect()
+    }
+
+    // to_text has a line for every passport, with every rule it breaks under it, then how many are
+    // valid and why the rest aren't.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for outcome in self.outcomes.iter() {
+            write!(
+                out,
+                "passport {} (line {}): ",
+                outcome.passport, outcome.line
+            )
+            .unwrap();
+            if outcome.is_valid() {
+                out.push_str("valid\n");
+            } else {
+                let reasons: Vec<String> =
+                    outcome.reasons.iter().map(|r| format!("{:?}", r)).collect();
+                writeln!(out, "invalid, {}", reasons.join(", ")).unwrap();
+                for violation in outcome.violations.iter() {
+                    writeln!(out, "    {}", violation).unwrap();
+                }
+            }
+        }
+
+        writeln!(
+            out,
+            "{} of {} passports are valid",
+            self.valid(),
+            self.outcomes.len()
+        )
+        .unwrap();
+        for (reason, count) in self.summary() {
+            if count > 0 {
+                writeln!(out, "  {:?}: {}", reason, count).unwrap();
+            }
+        }
+        out
+    }
+
+    // to_json is the same as to_text, for other tools to read. Every reason is in the summary,
+    // even the ones no passport fails for.
+    pub fn to_json(&self) -> String {
+        let strings = |strings: Vec<String>| -> String {
+            let quoted: Vec<String> = strings.iter().map(|s| quote(s)).collect();
+            format!("[{}]", quoted.join(", "))
+        };
+
+        let mut out = String::new();
+        out.push_str("{\n");
+        writeln!(out, "  \"passports\": {},", self.outcomes.len()).unwrap();
+        writeln!(out, "  \"valid\": {},", self.valid()).unwrap();
+        out.push_str("  \"reasons\": {");
+        let summary: Vec<String> = self
+            .summary()
+            .iter()
+            .map(|(reason, count)| format!("\"{:?}\": {}", reason, count))
+            .collect();
+        out.push_str(&summary.join(", "));
+        out.push_str("},\n");
+        out.push_str("  \"results\": [");
+        for (i, outcome) in self.outcomes.iter().enumerate() {
+            out.push_str(if i == 0 { "\n" } else { ",\n" });
+            write!(
+                out,
+                "    {{\"passport\": {}, \"line\": {}, \"valid\": {}, \"reasons\": {}, \"violations\": {}}}",
+                outcome.passport,
+                outcome.line,
+                outcome.is_valid(),
+                strings(outcome.reasons.iter().map(|r| format!("{:?}", r)).collect()),
+                strings(outcome.violations.iter().map(|v| v.to_string()).collect())
+            )
+            .unwrap();
+        }
+        if !self.outcomes.is_empty() {
+            out.push_str("\n  ");
+        }
+        out.push_str("]\n}\n");
+        out
+    }
+}
+
+// quote is text as a JSON string. Violations repeat whatever values they were given, so anything
+// can be in them.
+fn quote(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// start_lines is the line every passport in contents starts on, the same way parse_records splits
+// them up.
+fn start_lines(contents: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut in_passport = false;
+    for (i, line) in contents.lines().enumerate() {
+        let blank = line.trim().is_empty();
+        if !blank && !in_passport {
+            lines.push(i + 1);
+        }
+        in_passport = !blank;
+    }
+    lines
+}
+
+#[cfg(test)]
+const BATCH: &str = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013 eyr:2024 ecl:brn pid:760753108 byr:1931 hgt:179in
+
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 hgt:183cm pin:1234";
+
+#[test]
+fn test_report() {
+    let report = Report::new(BATCH).unwrap();
+    assert_eq!(
+        vec![1, 5, 8, 10, 13],
+        report.outcomes.iter().map(|o| o.line).collect::<Vec<_>>()
+    );
+    assert_eq!(1, report.valid());
+    assert_eq!(
+        vec![
+            Reason::EXPIRATION_INVALID,
+            Reason::ID_INVALID,
+            Reason::HEIGHT_INVALID
+        ],
+        report.outcomes[3].reasons
+    );
+    assert_eq!(vec![Reason::UNKNOWN_FIELDS], report.outcomes[4].reasons);
+    assert_eq!(
+        vec![
+            (Reason::MISSING_FIELDS, 1),
+            (Reason::BIRTH_INVALID, 0),
+            (Reason::ISSUE_INVALID, 0),
+            (Reason::EXPIRATION_INVALID, 1),
+            (Reason::EYE_INVALID, 0),
+            (Reason::ID_INVALID, 1),
+            (Reason::HAIR_INVALID, 0),
+            (Reason::HEIGHT_INVALID, 2),
+            (Reason::UNKNOWN_FIELDS, 1),
+            (Reason::REPEATED_FIELDS, 0),
+            (Reason::FIELD_INVALID, 0),
+        ],
+        report.summary()
+    );
+}
+
+#[test]
+fn test_text() {
+    let expected = "\
+passport 1 (line 1): valid
+passport 2 (line 5): invalid, MISSING_FIELDS
+    hgt is missing
+passport 3 (line 8): invalid, HEIGHT_INVALID
+    hgt:179in breaks in=59..76
+passport 4 (line 10): invalid, EXPIRATION_INVALID, ID_INVALID, HEIGHT_INVALID
+    eyr:1972 breaks range=2020..2030
+    hgt:170 breaks measure
+    pid:186cm breaks length=9
+    pid:186cm breaks regex=^[0-9]+$
+passport 5 (line 13): invalid, UNKNOWN_FIELDS
+    pin is not a known field
+1 of 5 passports are valid
+  MISSING_FIELDS: 1
+  EXPIRATION_INVALID: 1
+  ID_INVALID: 1
+  HEIGHT_INVALID: 2
+  UNKNOWN_FIELDS: 1
+";
+    assert_eq!(expected, Report::new(BATCH).unwrap().to_text());
+}
+
+#[test]
+fn test_json() {
+    let expected = r#"{
+  "passports": 5,
+  "valid": 1,
+  "reasons": {"MISSING_FIELDS": 1, "BIRTH_INVALID": 0, "ISSUE_INVALID": 0, "EXPIRATION_INVALID": 1, "EYE_INVALID": 0, "ID_INVALID": 1, "HAIR_INVALID": 0, "HEIGHT_INVALID": 2, "UNKNOWN_FIELDS": 1, "REPEATED_FIELDS": 0, "FIELD_INVALID": 0},
+  "results": [
+    {"passport": 1, "line": 1, "valid": true, "reasons": [], "violations": []},
+    {"passport": 2, "line": 5, "valid": false, "reasons": ["MISSING_FIELDS"], "violations": ["hgt is missing"]},
+    {"passport": 3, "line": 8, "valid": false, "reasons": ["HEIGHT_INVALID"], "violations": ["hgt:179in breaks in=59..76"]},
+    {"passport": 4, "line": 10, "valid": false, "reasons": ["EXPIRATION_INVALID", "ID_INVALID", "HEIGHT_INVALID"], "violations": ["eyr:1972 breaks range=2020..2030", "hgt:170 breaks measure", "pid:186cm breaks length=9", "pid:186cm breaks regex=^[0-9]+$"]},
+    {"passport": 5, "line": 13, "valid": false, "reasons": ["UNKNOWN_FIELDS"], "violations": ["pin is not a known field"]}
+  ]
+}
+"#;
+    assert_eq!(expected, Report::new(BATCH).unwrap().to_json());
+
+    let empty = Report::new("").unwrap().to_json();
+    assert!(empty.contains("\"results\": []\n}"), "{}", empty);
+}
+
+#[test]
+fn test_same_as_solve() {
+    // A repeated key and a value with a colon in are both read the way solve reads them.
+    let contents = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1800 byr:1937 iyr:2017 cid:147 hgt:183cm
+
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:12:30 hgt:183cm";
+    let report = Report::new(contents).unwrap();
+    assert_eq!(Ok(report.valid() as i32), crate::solve(contents));
+    assert_eq!(vec![Reason::REPEATED_FIELDS], report.outcomes[0].reasons);
+    assert!(report.outcomes[1].is_valid());
+}
+
+#[test]
+fn test_other_schema() {
+    let schema = Schema::parse("name required\nage optional number range=0..150\nstrict").unwrap();
+    let report = Report::with_schema(&schema, "name:ada age:36\n\n\nage:200\npet:\"cat\"").unwrap();
+    assert_eq!(
+        vec![
+            Reason::MISSING_FIELDS,
+            Reason::UNKNOWN_FIELDS,
+            Reason::FIELD_INVALID
+        ],
+        report.outcomes[1].reasons
+    );
+    assert!(report
+        .to_text()
+        .contains("passport 2 (line 4): invalid, MISSING_FIELDS, UNKNOWN_FIELDS, FIELD_INVALID\n"));
+    assert!(report.to_json().contains(
+        r#""violations": ["name is missing", "age:200 breaks range=0..150", "pet is not a known field"]"#
+    ));
+    assert_eq!(r#""a \"b\" \\ c""#, quote(r#"a "b" \ c"#));
+}
@@ -0,0 +1,516 @@
+use common::ParseError;
+use regex::Regex;
+use std::fmt;
+
+// PASSPORT_SCHEMA is the puzzle's passport rules written as a schema.
+pub const PASSPORT_SCHEMA: &str = include_str!("../passport.schema");
+
+// Kind is what sort of value a field holds.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Kind {
+    // Anything at all.
+    Text,
+    // A whole number.
+    Number,
+    // A whole number followed by a unit, like 183cm.
+    Measure,
+}
+
+// Rule is one thing a field's value has to do. Every rule prints the way it's written in a schema.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    // A number from the first to the second, including both.
+    Range(i64, i64),
+    // A measure in the given unit, from the first number to the second.
+    Unit(String, i64, i64),
+    // Matches the regex somewhere, so anchor it to match the whole value.
+    Regex(Regex),
+    // Exactly one of these.
+    OneOf(Vec<String>),
+    // This many characters long.
+    Length(usize),
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Range(low, high) => write!(f, "range={}..{}", low, high),
+            Rule::Unit(unit, low, high) => write!(f, "{}={}..{}", unit, low, high),
+            Rule::Regex(regex) => write!(f, "regex={}", regex),
+            Rule::OneOf(values) => write!(f, "one-of={}", values.join(",")),
+            Rule::Length(len) => write!(f, "length={}", len),
+        }
+    }
+}
+
+// Field is everything a schema says about one key.
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub key: String,
+    pub required: bool,
+    pub kind: Kind,
+    pub rules: Vec<Rule>,
+}
+
+// Record is the key:value pairs between two blank lines, in the order they were written. Nothing
+// about them is checked until a schema validates them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Record {
+    pub pairs: Vec<(String, String)>,
+}
+
+impl Record {
+    // values is every value given for key.
+    pub fn values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.pairs
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// Violation is one way a record breaks its schema.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Violation {
+    // A required key isn't there.
+    Missing(String),
+    // A key is there more than once.
+    Repeated(String),
+    // A key the schema doesn't have, when the schema is strict.
+    Unknown(String),
+    // A value breaks a rule, or isn't the kind of value the field holds.
+    Invalid {
+        key: String,
+        value: String,
+        rule: String,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::Missing(key) => write!(f, "{} is missing", key),
+            Violation::Repeated(key) => write!(f, "{} is given more than once", key),
+            Violation::Unknown(key) => write!(f, "{} is not a known field", key),
+            Violation::Invalid { key, value, rule } => {
+                write!(f, "{}:{} breaks {}", key, value, rule)
+            }
+        }
+    }
+}
+
+// Schema is the fields records are validated against. A strict schema doesn't allow any keys it
+// doesn't list. A schema file has a field on every line:
+//   <key> required|optional [text|number|measure] [rule ...]
+// where the rules are
+//   range=<low>..<high>   a number from low to high, including both
+//   <unit>=<low>..<high>  a measure in unit, from low to high; a measure needs at least one
+//   regex=<regex>         the value matches the regex
+//   one-of=<a>,<b>,...    the value is one of these
+//   length=<n>            the value is n characters long
+// and the word strict on a line of its own makes the schema strict. Lines starting with # are
+// comments.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    pub fields: Vec<Field>,
+    pub strict: bool,
+}
+
+impl Schema {
+    // passport is the puzzle's schema, from PASSPORT_SCHEMA.
+    pub fn passport() -> Schema {
+        Schema::parse(PASSPORT_SCHEMA).expect("passport.schema is a valid schema")
+    }
+
+    pub fn parse(contents: &str) -> Result<Schema, ParseError> {
+        let mut fields: Vec<Field> = Vec::new();
+        let mut strict = false;
+
+        for line in contents.lines() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let (key, presence, rest) = match words.as_slice() {
+                [] => continue,
+                [comment, ..] if comment.starts_with('#') => continue,
+                ["strict"] => {
+                    strict = true;
+                    continue;
+                }
+                [key] => {
+                    return Err(ParseError::at(
+                        contents,
+                        key,
+                        "Field needs to be required or optional",
+                    ))
+                }
+                [key, presence, rest @ ..] => (*key, *presence, rest),
+            };
+
+            if fields.iter().any(|f| f.key == key) {
+                return Err(ParseError::at(contents, key, "Field is already defined"));
+            }
+            let required = match presence {
+                "required" => true,
+                "optional" => false,
+                _ => {
+                    return Err(ParseError::at(
+                        contents,
+                        presence,
+                        "Expected required or optional",
+                    ))
+                }
+            };
+
+            let (kind, rules) = match rest.first() {
+                Some(&"text") => (Kind::Text, &rest[1..]),
+                Some(&"number") => (Kind::Number, &rest[1..]),
+                Some(&"measure") => (Kind::Measure, &rest[1..]),
+                _ => (Kind::Text, rest),
+            };
+            let rules = rules
+                .iter()
+                .map(|rule| parse_rule(contents, rule, kind))
+                .collect::<Result<Vec<Rule>, ParseError>>()?;
+            if kind == Kind::Measure && !rules.iter().any(|r| matches!(r, Rule::Unit(..))) {
+                return Err(ParseError::at(
+                    contents,
+                    key,
+                    "A measure needs at least one unit",
+                ));
+            }
+
+            fields.push(Field {
+                key: key.to_string(),
+                required,
+                kind,
+                rules,
+            });
+        }
+
+        Ok(Schema { fields, strict })
+    }
+
+    // validate checks record against every field, and returns every rule it breaks, in the order
+    // the schema lists them. Nothing means the record is valid.
+    pub fn validate(&self, record: &Record) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for field in self.fields.iter() {
+            let values: Vec<&str> = record.values(&field.key).collect();
+            match values.as_slice() {
+                [] if field.required => violations.push(Violation::Missing(field.key.clone())),
+                [] => {}
+                [value] => check(field, value, &mut violations),
+                _ => violations.push(Violation::Repeated(field.key.clone())),
+            }
+        }
+
+        if self.strict {
+            for (key, _) in record.pairs.iter() {
+                let unknown = Violation::Unknown(key.clone());
+                if !self.fields.iter().any(|f| &f.key == key) && !violations.contains(&unknown) {
+                    violations.push(unknown);
+                }
+            }
+        }
+        violations
+    }
+}
+
+fn parse_rule(contents: &str, rule: &str, kind: Kind) -> Result<Rule, ParseError> {
+    let (name, value) = match rule.find('=') {
+        Some(i) => (&rule[..i], &rule[i + 1..]),
+        None => return Err(ParseError::at(contents, rule, "Rule needs a value")),
+    };
+
+    let range = || -> Result<(i64, i64), ParseError> {
+        let err = || ParseError::at(contents, value, "Not a range like 1..10");
+        let dots = value.find("..").ok_or_else(err)?;
+        let low = value[..dots].parse().map_err(|_| err())?;
+        let high = value[dots + 2..].parse().map_err(|_| err())?;
+        Ok((low, high))
+    };
+
+    match name {
+        "range" if kind == Kind::Number => {
+            let (low, high) = range()?;
+            Ok(Rule::Range(low, high))
+        }
+        "range" => Err(ParseError::at(
+            contents,
+            rule,
+            "Only a number can have a range",
+        )),
+        "regex" => match Regex::new(value) {
+            Ok(regex) => Ok(Rule::Regex(regex)),
+            Err(_) => Err(ParseError::at(contents, value, "Not a regex")),
+        },
+        "one-of" => Ok(Rule::OneOf(
+            value.split(',').map(|v| v.to_string()).collect(),
+        )),
+        "length" => match value.parse() {
+            Ok(len) => Ok(Rule::Length(len)),
+            Err(_) => Err(ParseError::at(contents, value, "Not a length")),
+        },
+        unit if kind == Kind::Measure && unit.chars().all(|c| c.is_ascii_alphabetic()) => {
+            let (low, high) = range()?;
+            Ok(Rule::Unit(unit.to_string(), low, high))
+        }
+        _ => Err(ParseError::at(contents, name, "Unknown rule")),
+    }
+}
+
+// check adds every way value breaks field's rules to violations.
+fn check(field: &Field, value: &str, violations: &mut Vec<Violation>) {
+    let mut broken = |rule: String| {
+        violations.push(Violation::Invalid {
+            key: field.key.clone(),
+            value: value.to_string(),
+            rule,
+        })
+    };
+
+    // The number in the value and the unit after it, if it's a number or measure and has them.
+    let (number, unit) = match field.kind {
+        Kind::Text => (None, None),
+        Kind::Number => match value.parse::<i64>() {
+            Ok(n) => (Some(n), None),
+            Err(_) => {
+                broken("number".to_string());
+                (None, None)
+            }
+        },
+        Kind::Measure => {
+            let split = value
+                .find(|c: char| !c.is_ascii_digit() && c != '-')
+                .unwrap_or(value.len());
+            let unit = &value[split..];
+            let known = field
+                .rules
+                .iter()
+                .any(|r| matches!(r, Rule::Unit(u, _, _) if u == unit));
+            match value[..split].parse::<i64>() {
+                Ok(n) if known => (Some(n), Some(unit)),
+                _ => {
+                    broken("measure".to_string());
+                    (None, None)
+                }
+            }
+        }
+    };
+
+    for rule in field.rules.iter() {
+        let ok = match rule {
+            Rule::Range(low, high) => number.is_none_or(|n| n >= *low && n <= *high),
+            Rule::Unit(u, low, high) => match (number, unit) {
+                (Some(n), Some(unit)) if unit == u => n >= *low && n <= *high,
+                _ => true,
+            },
+            Rule::Regex(regex) => regex.is_match(value),
+            Rule::OneOf(values) => values.iter().any(|v| v == value),
+            Rule::Length(len) => value.chars().count() == *len,
+        };
+        if !ok {
+            broken(rule.to_string());
+        }
+    }
+}
+
+// parse_records splits contents into records separated by blank lines, each of them key:value
+// pairs separated by whitespace. Keys can be anything but empty, and values can have colons in.
+pub fn parse_records(contents: &str) -> Result<Vec<Record>, ParseError> {
+    let mut records = Vec::new();
+    let mut pairs = Vec::new();
+    for line in contents.lines() {
+        if line.trim().is_empty() {
+            if !pairs.is_empty() {
+                records.push(Record { pairs });
+            }
+            pairs = Vec::new();
+            continue;
+        }
+
+        for pair in line.split_whitespace() {
+            match pair.find(':') {
+                Some(i) if i > 0 => pairs.push((pair[..i].to_string(), pair[i + 1..].to_string())),
+                _ => {
+                    return Err(ParseError::at(
+                        contents,
+                        pair,
+                        "Not properly formatted key-value pairs",
+                    ))
+                }
+            }
+        }
+    }
+    if !pairs.is_empty() {
+        records.push(Record { pairs });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+fn record(line: &str) -> Record {
+    parse_records(line).unwrap().remove(0)
+}
+
+#[test]
+fn test_parse_schema() {
+    let schema = Schema::parse(PASSPORT_SCHEMA).unwrap();
+    assert_eq!(8, schema.fields.len());
+    assert!(schema.strict);
+    let hgt = &schema.fields[3];
+    assert_eq!(
+        ("hgt", true, Kind::Measure),
+        (hgt.key.as_str(), hgt.required, hgt.kind)
+    );
+    assert_eq!(
+        vec!["cm=150..193", "in=59..76"],
+        hgt.rules.iter().map(|r| r.to_string()).collect::<Vec<_>>()
+    );
+    let cid = &schema.fields[7];
+    assert_eq!(
+        (false, Kind::Text, 0),
+        (cid.required, cid.kind, cid.rules.len())
+    );
+}
+
+#[test]
+fn test_schema_errors() {
+    let cases = [
+        (
+            "a",
+            ParseError::new(1, 1, "a", "Field needs to be required or optional"),
+        ),
+        (
+            "a needed",
+            ParseError::new(1, 3, "needed", "Expected required or optional"),
+        ),
+        (
+            "a optional\na required",
+            ParseError::new(2, 1, "a", "Field is already defined"),
+        ),
+        (
+            "a required text range=1..2",
+            ParseError::new(1, 17, "range=1..2", "Only a number can have a range"),
+        ),
+        (
+            "a required number range=1-2",
+            ParseError::new(1, 25, "1-2", "Not a range like 1..10"),
+        ),
+        (
+            "a required measure",
+            ParseError::new(1, 1, "a", "A measure needs at least one unit"),
+        ),
+        (
+            "a required measure cm=x..2",
+            ParseError::new(1, 23, "x..2", "Not a range like 1..10"),
+        ),
+        (
+            "a required regex=(",
+            ParseError::new(1, 18, "(", "Not a regex"),
+        ),
+        (
+            "a required length=-1",
+            ParseError::new(1, 19, "-1", "Not a length"),
+        ),
+        (
+            "a required cm=1..2",
+            ParseError::new(1, 12, "cm", "Unknown rule"),
+        ),
+        (
+            "a required upper",
+            ParseError::new(1, 12, "upper", "Rule needs a value"),
+        ),
+    ];
+    for (contents, err) in cases.iter() {
+        assert_eq!(
+            Err(err.clone()),
+            Schema::parse(contents).map(|_| ()),
+            "{}",
+            contents
+        );
+    }
+}
+
+#[test]
+fn test_every_violation() {
+    let schema = Schema::parse(PASSPORT_SCHEMA).unwrap();
+    assert_eq!(
+        Vec::<Violation>::new(),
+        schema.validate(&record(
+            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f"
+        ))
+    );
+
+    let violations = schema.validate(&record(
+        "byr:1919 iyr:x hgt:190in hgt:190cm hcl:123abc ecl:red pid:0123456x cid:1 zip:9",
+    ));
+    let messages: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+    assert_eq!(
+        vec![
+            "byr:1919 breaks range=1920..2002",
+            "iyr:x breaks number",
+            "eyr is missing",
+            "hgt is given more than once",
+            "hcl:123abc breaks regex=^#[0-9a-fA-F]{6}$",
+            "ecl:red breaks one-of=amb,blu,brn,gry,grn,hzl,oth",
+            "pid:0123456x breaks length=9",
+            "pid:0123456x breaks regex=^[0-9]+$",
+            "zip is not a known field",
+        ],
+        messages
+    );
+}
+
+#[test]
+fn test_measures() {
+    let schema = Schema::parse("hgt required measure cm=150..193 in=59..76").unwrap();
+    let broken = |value: &str| -> Vec<String> {
+        schema
+            .validate(&record(&format!("hgt:{}", value)))
+            .iter()
+            .map(|v| v.to_string())
+            .collect()
+    };
+    assert!(broken("150cm").is_empty());
+    assert!(broken("76in").is_empty());
+    assert_eq!(vec!["hgt:77in breaks in=59..76"], broken("77in"));
+    assert_eq!(vec!["hgt:190 breaks measure"], broken("190"));
+    assert_eq!(vec!["hgt:59ft breaks measure"], broken("59ft"));
+    assert_eq!(vec!["hgt:cm breaks measure"], broken("cm"));
+}
+
+#[test]
+fn test_arbitrary_records() {
+    // Unknown keys are fine when the schema isn't strict, and values can have colons in.
+    let schema = Schema::parse("name required\nage optional number range=0..150").unwrap();
+    let records = parse_records("name:ada time:12:30\n\nage:200").unwrap();
+    assert_eq!(
+        vec![
+            ("name".to_string(), "ada".to_string()),
+            ("time".to_string(), "12:30".to_string())
+        ],
+        records[0].pairs
+    );
+    assert!(schema.validate(&records[0]).is_empty());
+    assert_eq!(
+        vec![
+            Violation::Missing("name".to_string()),
+            Violation::Invalid {
+                key: "age".to_string(),
+                value: "200".to_string(),
+                rule: "range=0..150".to_string()
+            }
+        ],
+        schema.validate(&records[1])
+    );
+
+    assert_eq!(
+        Err(ParseError::new(
+            1,
+            10,
+            ":x",
+            "Not properly formatted key-value pairs"
+        )),
+        parse_records("name:ada :x")
+    );
+}
@@ -0,0 +1,349 @@
+use common::ParseError;
+
+pub mod map;
+
+// Layout is how many rows and columns of seats a plane has. A boarding pass has a letter for each
+// binary digit of the row, F for 0 and B for 1, then one for each digit of the column, L for 0 and
+// R for 1, with the most significant digit first.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Layout {
+    pub rows: i32,
+    pub columns: i32,
+}
+
+impl Layout {
+    // PUZZLE is the plane in the puzzle, with boarding passes like FBFBBFFRLR.
+    pub const PUZZLE: Layout = Layout {
+        rows: 128,
+        columns: 8,
+    };
+
+    pub fn new(rows: i32, columns: i32) -> Result<Layout, String> {
+        if rows < 1 || columns < 1 {
+            return Err(format!(
+                "A plane can't have {} rows of {} seats",
+                rows, columns
+            ));
+        }
+        // Every seat needs an id, and the last one is rows * columns - 1.
+        if rows.checked_mul(columns).is_none() {
+            return Err(format!(
+                "{} rows of {} seats is too many to number",
+                rows, columns
+            ));
+        }
+        Ok(Layout { rows, columns })
+    }
+
+    // bits is how many binary digits it takes to number count things from 0.
+    fn bits(count: i32) -> usize {
+        (32 - (count - 1).leading_zeros()) as usize
+    }
+
+    pub fn row_letters(&self) -> usize {
+        Layout::bits(self.rows)
+    }
+
+    pub fn column_letters(&self) -> usize {
+        Layout::bits(self.columns)
+    }
+
+    // encode is the boarding pass for the seat at row and column.
+    pub fn encode(&self, row: i32, column: i32) -> Result<String, String> {
+        if row < 0 || row >= self.rows || column < 0 || column >= self.columns {
+            return Err(format!(
+                "There's no seat at row {}, column {} on a plane with {} rows of {} seats",
+                row, column, self.rows, self.columns
+            ));
+        }
+
+        let digits = |n: i32, len: usize, zero: char, one: char| -> String {
+            (0..len)
+                .rev()
+                .map(|bit| if n >> bit & 1 == 1 { one } else { zero })
+                .collect()
+        };
+        let row = digits(row, self.row_letters(), 'F', 'B');
+        Ok(row + &digits(column, self.column_letters(), 'L', 'R'))
+    }
+
+    // decode reads a single boarding pass, so any error it gives is on line 1.
+    pub fn decode(&self, contents: &str) -> Result<Passenger, ParseError> {
+        let mapping: Vec<char> = contents.chars().collect();
+        let len = self.row_letters() + self.column_letters();
+        if mapping.len() != len {
+            let message = format!("Boarding passes are {} letters", len);
+            return Err(ParseError::new(1, 1, contents, &message));
+        }
+        let wrong_code = |i: usize| {
+            ParseError::new(
+                1,
+                i + 1,
+                &mapping[i].to_string(),
+                "Didn't have the correct codes",
+            )
+        };
+
+        let mut row = 0;
+        for (i, code) in mapping.iter().enumerate().take(self.row_letters()) {
+            row = match code {
+                'F' => row * 2,
+                'B' => row * 2 + 1,
+                _ => return Err(wrong_code(i)),
+            };
+        }
+
+        let mut column = 0;
+        for (i, code) in mapping.iter().enumerate().skip(self.row_letters()) {
+            column = match code {
+                'L' => column * 2,
+                'R' => column * 2 + 1,
+                _ => return Err(wrong_code(i)),
+            };
+        }
+
+        if row >= self.rows || column >= self.columns {
+            return Err(ParseError::new(
+                1,
+                1,
+                contents,
+                "No such seat on this plane",
+            ));
+        }
+        Ok(Passenger {
+            id: self.columns * row + column,
+            row,
+            column,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct Passenger {
+    pub id: i32,
+    pub row: i32,
+    pub column: i32,
+}
+
+impl Passenger {
+    // parse reads a single boarding pass for the puzzle's plane.
+    pub fn parse(contents: &str) -> Result<Passenger, ParseError> {
+        Layout::PUZZLE.decode(contents)
+    }
+}
+
+#[test]
+fn test_samples() {
+    assert_eq!(
+        Passenger {
+            id: 357,
+            row: 44,
+            column: 5
+        },
+        Passenger::parse("FBFBBFFRLR").unwrap()
+    );
+    assert_eq!(
+        Passenger {
+            id: 567,
+            row: 70,
+            column: 7
+        },
+        Passenger::parse("BFFFBBFRRR").unwrap()
+    );
+    assert_eq!(
+        Passenger {
+            id: 119,
+            row: 14,
+            column: 7
+        },
+        Passenger::parse("FFFBBBFRRR").unwrap()
+    );
+    assert_eq!(
+        Passenger {
+            id: 820,
+            row: 102,
+            column: 4
+        },
+        Passenger::parse("BBFFBBFRLL").unwrap()
+    );
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Airplane {
+    pub passengers: Vec<Passenger>,
+    pub layout: Layout,
+}
+
+impl Airplane {
+    pub fn parse(contents: &str) -> Result<Airplane, ParseError> {
+        Airplane::parse_with(contents, Layout::PUZZLE)
+    }
+
+    pub fn parse_with(contents: &str, layout: Layout) -> Result<Airplane, ParseError> {
+        let mut passengers = Vec::new();
+        for (n, line) in contents.lines().enumerate() {
+            let passenger = layout
+                .decode(line)
+                .map_err(|e| ParseError { line: n + 1, ..e })?;
+            passengers.push(passenger);
+        }
+        return Ok(Airplane {
+            passengers: passengers,
+            layout,
+        });
+    }
+
+    pub fn max_id(&self) -> Option<&Passenger> {
+        let mut iter = self.passengers.iter();
+        let mut max: Option<&Passenger> = iter.next();
+        for passenger in iter {
+            if let Some(current_max) = max {
+                if passenger.id > current_max.id {
+                    max = Some(passenger);
+                }
+            }
+        }
+
+        return max;
+    }
+
+    // missing_seats walks the seat ids in order and reports the first id of every gap.
+    pub fn missing_seats(&mut self) -> Vec<i32> {
+        let mut missing = Vec::new();
+        self.passengers.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut iter = self.passengers.iter();
+        let mut previous_passenger = match iter.next() {
+            Some(p) => p,
+            None => return missing,
+        };
+        for passenger in iter {
+            let next_passenger_id = previous_passenger.id + 1;
+
+            if passenger.id != next_passenger_id {
+                missing.push(next_passenger_id);
+            }
+
+            previous_passenger = passenger;
+        }
+        return missing;
+    }
+}
+
+// Day05 is the boarding passes.
+pub struct Day05;
+
+impl common::Solver for Day05 {
+    type Input<'a> = Airplane;
+
+    fn parse(contents: &str) -> Result<Airplane, ParseError> {
+        Airplane::parse(contents)
+    }
+
+    fn part1(airplane: &Airplane) -> Result<String, String> {
+        match airplane.max_id() {
+            Some(passenger) => Ok(passenger.id.to_string()),
+            None => Err("No passengers on the plane".to_string()),
+        }
+    }
+
+    // part2 is our seat: the only missing seat with someone sitting on either side of it.
+    fn part2(airplane: &Airplane) -> Result<String, String> {
+        match airplane.clone().missing_seats().first() {
+            Some(id) => Ok(id.to_string()),
+            None => Err("No missing seats".to_string()),
+        }
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day05>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day05>(contents)
+}
+
+#[test]
+fn test_encode() {
+    for pass in ["FBFBBFFRLR", "BFFFBBFRRR", "FFFBBBFRRR", "BBFFBBFRLL"].iter() {
+        let passenger = Passenger::parse(pass).unwrap();
+        assert_eq!(
+            Ok(pass.to_string()),
+            Layout::PUZZLE.encode(passenger.row, passenger.column)
+        );
+    }
+    assert!(Layout::PUZZLE.encode(128, 0).is_err());
+    assert!(Layout::PUZZLE.encode(0, -1).is_err());
+}
+
+#[test]
+fn test_other_layouts() {
+    // 10 rows take 4 letters and 3 columns take 2, and not every code is a seat.
+    let layout = Layout::new(10, 3).unwrap();
+    assert_eq!(Ok("BFFBRL".to_string()), layout.encode(9, 2));
+    assert_eq!(
+        Ok(Passenger {
+            id: 29,
+            row: 9,
+            column: 2
+        }),
+        layout.decode("BFFBRL")
+    );
+    assert_eq!(
+        Err(ParseError::new(
+            1,
+            1,
+            "BFBFLL",
+            "No such seat on this plane"
+        )),
+        layout.decode("BFBFLL")
+    );
+    assert_eq!(
+        Err(ParseError::new(
+            1,
+            1,
+            "FBFBBFFRLR",
+            "Boarding passes are 6 letters"
+        )),
+        layout.decode("FBFBBFFRLR")
+    );
+
+    // A single column doesn't need any letters at all.
+    let layout = Layout::new(4, 1).unwrap();
+    assert_eq!(Ok("BF".to_string()), layout.encode(2, 0));
+    assert_eq!(Ok(2), layout.decode("BF").map(|p| p.id));
+
+    assert!(Layout::new(0, 8).is_err());
+    assert_eq!(
+        Err("65536 rows of 32768 seats is too many to number".to_string()),
+        Layout::new(65536, 32768)
+    );
+    assert!(Layout::new(65536, 32767).is_ok());
+}
+
+#[test]
+fn test_missing_seats() {
+    let contents = "\
+FBFBBFFRLL
+FBFBBFFRLR
+FBFBBFFRRR";
+    assert_eq!(Ok("359".to_string()), part1(contents));
+    assert_eq!(Ok("358".to_string()), part2(contents));
+}
+
+#[test]
+fn test_bad_boarding_passes() {
+    assert_eq!(
+        Err(ParseError::new(2, 8, "X", "Didn't have the correct codes")),
+        Airplane::parse("FBFBBFFRLL\nFBFBBFFXLR")
+    );
+    assert_eq!(
+        Err(ParseError::new(
+            1,
+            1,
+            "FBFBBFFRL",
+            "Boarding passes are 10 letters"
+        )),
+        Passenger::parse("FBFBBFFRL")
+    );
+}
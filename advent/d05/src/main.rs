@@ -1,146 +1,177 @@
-use clap::{App, Arg};
+use clap::{App, Arg, ArgMatches};
+use d05::{map, Airplane, Layout};
 use std::fs;
+use std::process;
 
-#[derive(Debug, PartialEq, PartialOrd)]
-struct Passenger {
-    id: i32,
-    row: i32,
-    column: i32,
+// app is the command line: the plane's layout, then what to do with it.
+fn app() -> App<'static> {
+    App::new("Reader for advent")
+        .version("1.0")
+        .author("Sean Tyler Myers <seanmyers0608@gmail.com>")
+        .about("Finds seats on a plane from their boarding passes")
+        .subcommand_required(true)
+        .arg(
+            Arg::new("rows")
+                .long("rows")
+                .takes_value(true)
+                .value_name("n")
+                .global(true)
+                .help("Sets how many rows the plane has"),
+        )
+        .arg(
+            Arg::new("columns")
+                .long("columns")
+                .takes_value(true)
+                .value_name("n")
+                .global(true)
+                .help("Sets how many seats are in a row"),
+        )
+        .subcommand(
+            App::new("map")
+                .about("Draws the seats the passes are for")
+                .arg(
+                    Arg::new("INPUT")
+                        .help("Sets the input file to use")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            App::new("encode")
+                .about("Finds the pass for a seat")
+                .arg(Arg::new("ROW").required(true).index(1))
+                .arg(Arg::new("COLUMN").required(true).index(2)),
+        )
+        .subcommand(
+            App::new("decode")
+                .about("Finds the seat for a pass")
+                .arg(Arg::new("PASS").required(true).index(1)),
+        )
 }
 
-impl Passenger {
-    fn parse(contents: &str) -> Result<Passenger, &'static str> {
-        let mut lower = 0;
-        let mut upper = 127;
+// Command is what to do with the plane.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Map(String),
+    Encode(i32, i32),
+    Decode(String),
+}
 
-        let mapping: Vec<char> = contents.chars().collect();
-        for i in (0..7) {
-            let current = (upper + lower) / 2;
-            let remainder = (upper + lower) % 2;
-            match mapping.get(i).unwrap() {
-                'F' => upper = current,
-                'B' => lower = current + remainder,
-                _ => return Err("Didn't have the correct codes"),
-            };
-        }
+// Config is the plane's layout, which is the puzzle's unless --rows or --columns change it, and
+// what to do with it.
+#[derive(Debug, PartialEq)]
+struct Config {
+    layout: Layout,
+    command: Command,
+}
 
-        let partition = lower;
-        let mut lower = 0;
-        let mut upper = 7;
-        for i in (7..10) {
-            let current = (upper + lower) / 2;
-            let remainder = (upper + lower) % 2;
-            match mapping.get(i).unwrap() {
-                'L' => upper = current,
-                'R' => lower = current + remainder,
-                _ => return Err("Didn't have the correct codes"),
-            };
-        }
+impl Config {
+    fn new(matches: &ArgMatches) -> Result<Config, String> {
+        let (name, sub) = matches.subcommand().unwrap();
+        let seats = |arg: &str, default: i32| -> Result<i32, String> {
+            match sub.value_of(arg) {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| format!("{} is not a number of seats", value)),
+                None => Ok(default),
+            }
+        };
+        let layout = Layout::new(
+            seats("rows", Layout::PUZZLE.rows)?,
+            seats("columns", Layout::PUZZLE.columns)?,
+        )?;
 
-        let seat_col = lower;
-        return Ok(Passenger {
-            id: 8 * partition + seat_col,
-            row: partition,
-            column: seat_col,
-        });
+        let command = match name {
+            "map" => Command::Map(sub.value_of("INPUT").unwrap().to_string()),
+            "encode" => Command::Encode(
+                number(sub.value_of("ROW").unwrap())?,
+                number(sub.value_of("COLUMN").unwrap())?,
+            ),
+            _ => Command::Decode(sub.value_of("PASS").unwrap().to_string()),
+        };
+        Ok(Config { layout, command })
     }
 }
 
-#[test]
-fn test_samples() {
-    assert_eq!(
-        Passenger {
-            id: 357,
-            row: 44,
-            column: 5
-        },
-        Passenger::parse("FBFBBFFRLR").unwrap()
-    );
-    assert_eq!(
-        Passenger {
-            id: 567,
-            row: 70,
-            column: 7
-        },
-        Passenger::parse("BFFFBBFRRR").unwrap()
-    );
-    assert_eq!(
-        Passenger {
-            id: 119,
-            row: 14,
-            column: 7
-        },
-        Passenger::parse("FFFBBBFRRR").unwrap()
-    );
-    assert_eq!(
-        Passenger {
-            id: 820,
-            row: 102,
-            column: 4
-        },
-        Passenger::parse("BBFFBBFRLL").unwrap()
-    );
+fn number(text: &str) -> Result<i32, String> {
+    text.parse()
+        .map_err(|_| format!("{} is not a number", text))
 }
 
-#[derive(Debug, PartialEq)]
-struct Airplane {
-    passengers: Vec<Passenger>,
-}
+fn main() {
+    let matches = app().get_matches();
+    let config = Config::new(&matches).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}", err);
+        process::exit(1);
+    });
+    let layout = config.layout;
 
-impl Airplane {
-    fn parse(contents: &str) -> Result<Airplane, &'static str> {
-        let mut passengers = Vec::new();
-        for line in contents.lines() {
-            let passenger = Passenger::parse(line)?;
-            passengers.push(passenger);
+    let result = match config.command {
+        Command::Map(filename) => {
+            let contents = fs::read_to_string(&filename).unwrap_or_else(|err| {
+                eprintln!("Problem reading {}: {}", filename, err);
+                process::exit(1);
+            });
+            let airplane = Airplane::parse_with(&contents, layout).unwrap_or_else(|err| {
+                eprintln!("Problem parsing {}", err.in_file(&filename));
+                process::exit(1);
+            });
+            Ok(map::render(&airplane))
         }
-        return Ok(Airplane {
-            passengers: passengers,
-        });
-    }
-
-    fn max_id(&self) -> Option<&Passenger> {
-        let mut iter = self.passengers.iter();
-        let mut max: Option<&Passenger> = iter.next();
-        for passenger in iter {
-            if let Some(current_max) = max {
-                if passenger.id > current_max.id {
-                    max = Some(passenger);
-                }
-            }
+        Command::Encode(row, column) => {
+            layout.encode(row, column).map(|pass| format!("{}\n", pass))
         }
+        Command::Decode(pass) => layout
+            .decode(&pass)
+            .map(|p| format!("row {}, column {}, id {}\n", p.row, p.column, p.id))
+            .map_err(String::from),
+    };
 
-        return max;
+    match result {
+        Ok(out) => print!("{}", out),
+        Err(err) => {
+            eprintln!("Problem: {}", err);
+            process::exit(1);
+        }
     }
 }
 
-fn main() {
-    let matches = App::new("Reader for advent")
-        .version("1.0")
-        .author("Sean Tyler Myers <seanmyers0608@gmail.com>")
-        .about("Reads advent calendar programs")
-        .arg(
-            Arg::new("INPUT")
-                .about("Sets the input file to use")
-                .required(true)
-                .index(1),
-        )
-        .get_matches();
-
-    let txt_location: &str = matches.value_of("INPUT").unwrap();
-    let contents = fs::read_to_string(txt_location).unwrap();
-    let mut airplane = Airplane::parse(&contents).unwrap();
-    println!("{}", airplane.max_id().unwrap().id);
-    airplane.passengers.sort_by((|a, b| a.id.cmp(&b.id)));
-    let mut iter = airplane.passengers.iter();
-    let mut previous_passenger = iter.next().unwrap();
-    for passenger in iter {
-        let next_passenger_id = previous_passenger.id + 1;
-
-        if passenger.id != next_passenger_id {
-            println!("Missing seat {:?}", next_passenger_id);
-        }
+#[cfg(test)]
+fn config(args: &[&str]) -> Result<Config, String> {
+    let matches = app()
+        .try_get_matches_from(std::iter::once("d05").chain(args.iter().copied()))
+        .map_err(|err| err.to_string())?;
+    Config::new(&matches)
+}
 
-        previous_passenger = passenger;
-    }
+#[test]
+fn test_config() {
+    assert_eq!(
+        Ok(Config {
+            layout: Layout::PUZZLE,
+            command: Command::Map("passes.txt".to_string())
+        }),
+        config(&["map", "passes.txt"])
+    );
+    assert_eq!(
+        Ok(Config {
+            layout: Layout {
+                rows: 40,
+                columns: 6
+            },
+            command: Command::Encode(1, 2)
+        }),
+        config(&["--rows", "40", "encode", "--columns", "6", "1", "2"])
+    );
+    assert_eq!(
+        Err("many is not a number of seats".to_string()),
+        config(&["--rows", "many", "map", "passes.txt"])
+    );
+    assert_eq!(
+        Err("x is not a number".to_string()),
+        config(&["encode", "x", "2"])
+    );
+    assert!(config(&["--columns", "0", "map", "passes.txt"]).is_err());
+    assert!(config(&["--rows"]).is_err());
+    assert!(config(&["fly"]).is_err());
 }
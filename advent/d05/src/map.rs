@@ -0,0 +1,122 @@
+use crate::Airplane;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+// Seat is what's at a spot on the plane.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Seat {
+    // Someone has a boarding pass for it.
+    Occupied,
+    // Nobody has a boarding pass for it, but it's between the first and last seats that somebody
+    // does, so it's there.
+    Empty,
+    // It's in front of the first boarding pass or behind the last, where this plane has no seats.
+    Missing,
+}
+
+impl Seat {
+    pub fn symbol(&self) -> char {
+        match self {
+            Seat::Occupied => '#',
+            Seat::Empty => '.',
+            Seat::Missing => ' ',
+        }
+    }
+}
+
+// seats is what's at every spot on airplane, row by row.
+pub fn seats(airplane: &Airplane) -> Vec<Vec<Seat>> {
+    let ids: HashSet<i32> = airplane.passengers.iter().map(|p| p.id).collect();
+    let first = ids.iter().min().copied().unwrap_or(0);
+    let last = ids.iter().max().copied().unwrap_or(-1);
+
+    let layout = airplane.layout;
+    (0..layout.rows)
+        .map(|row| {
+            (0..layout.columns)
+                .map(|column| {
+                    let id = layout.columns * row + column;
+                    if ids.contains(&id) {
+                        Seat::Occupied
+                    } else if id > first && id < last {
+                        Seat::Empty
+                    } else {
+                        Seat::Missing
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// render draws airplane's seats, front row first, with the row numbers down the side and the last
+// digit of every column number along the top. Rows without any seats are left out.
+pub fn render(airplane: &Airplane) -> String {
+    let width = (airplane.layout.rows - 1).to_string().len();
+    let mut out = String::new();
+    write!(out, "{:width$} ", "", width = width).unwrap();
+    for column in 0..airplane.layout.columns {
+        write!(out, "{}", column % 10).unwrap();
+    }
+    out.push('\n');
+
+    for (row, seats) in seats(airplane).iter().enumerate() {
+        if seats.iter().all(|seat| *seat == Seat::Missing) {
+            continue;
+        }
+        let line: String = seats.iter().map(|seat| seat.symbol()).collect();
+        writeln!(out, "{:>width$} {}", row, line.trim_end(), width = width).unwrap();
+    }
+    out.push_str("# occupied, . empty, blank for no seat\n");
+    out
+}
+
+#[cfg(test)]
+fn airplane(rows: i32, columns: i32, seats: &[(i32, i32)]) -> Airplane {
+    let layout = crate::Layout::new(rows, columns).unwrap();
+    let passes: Vec<String> = seats
+        .iter()
+        .map(|(row, column)| layout.encode(*row, *column).unwrap())
+        .collect();
+    Airplane::parse_with(&passes.join("\n"), layout).unwrap()
+}
+
+#[test]
+fn test_seats() {
+    let plane = airplane(3, 2, &[(0, 1), (2, 0), (1, 1)]);
+    assert_eq!(
+        vec![
+            vec![Seat::Missing, Seat::Occupied],
+            vec![Seat::Empty, Seat::Occupied],
+            vec![Seat::Occupied, Seat::Missing],
+        ],
+        seats(&plane)
+    );
+}
+
+#[test]
+fn test_render() {
+    let mut taken = Vec::new();
+    for row in 1..11 {
+        for column in 0..4 {
+            if (row, column) != (5, 2) && (row, column) != (10, 3) {
+                taken.push((row, column));
+            }
+        }
+    }
+    taken.retain(|seat| *seat != (1, 0));
+    let expected = "   0123
+ 1  ###
+ 2 ####
+ 3 ####
+ 4 ####
+ 5 ##.#
+ 6 ####
+ 7 ####
+ 8 ####
+ 9 ####
+10 ###
+# occupied, . empty, blank for no seat
+";
+    assert_eq!(expected, render(&airplane(12, 4, &taken)));
+}
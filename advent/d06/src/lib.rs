@@ -0,0 +1,121 @@
+use common::ParseError;
+use std::collections::HashMap;
+
+// count_any adds up, for every group, how many questions anyone in the group answered yes to.
+pub fn count_any(contents: &str) -> usize {
+    let mut counts: HashMap<char, bool> = HashMap::new();
+    let mut full_count = 0;
+    let lines:Vec<&str> = contents.lines().collect();
+    for line in lines.iter() {
+        if line.to_owned() == "" {
+            full_count += counts.len();
+            counts = HashMap::new();
+        }
+
+        for c in line.chars(){
+            counts.insert(c, true);
+        }
+    }
+
+    full_count += counts.len();
+    return full_count;
+}
+
+// count_all is the harder problem: only questions everyone in the group answered yes to count.
+pub fn count_all(contents: &str) -> usize {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut counts: HashMap<char, u32> = HashMap::new();
+    let mut people = 0;
+    let mut full_count = 0;
+    for line in lines.iter() {
+        if line.to_owned() == "" {
+            for (_,amt) in counts {
+                if amt == people {
+                    full_count += 1;
+                }
+            }
+            counts = HashMap::new();
+            people = 0;
+            continue;
+        }
+
+        for c in line.chars(){
+            let char_count = counts.get(&c).unwrap_or(&0);
+            let total = char_count + 1;
+            counts.insert(c, total);
+        }
+        people += 1;
+    }
+    for (_,amt) in counts {
+            if amt == people {
+                full_count += 1;
+            }
+    }
+
+    return full_count;
+}
+
+// Day06 is the customs declaration forms.
+pub struct Day06;
+
+impl common::Solver for Day06 {
+    type Input<'a> = &'a str;
+
+    // The groups get split up as they're counted, so parse only checks every answer is a question.
+    fn parse(contents: &str) -> Result<&str, ParseError> {
+        for line in contents.lines() {
+            if let Some(i) = line.find(|c: char| !c.is_ascii_lowercase()) {
+                let c = line[i..].chars().next().unwrap();
+                return Err(ParseError::at(contents, &line[i..i + c.len_utf8()], "Questions are a to z"));
+            }
+        }
+        Ok(contents)
+    }
+
+    fn part1(contents: &&str) -> Result<String, String> {
+        Ok(count_any(contents).to_string())
+    }
+
+    fn part2(contents: &&str) -> Result<String, String> {
+        Ok(count_all(contents).to_string())
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day06>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day06>(contents)
+}
+
+#[test]
+fn test_group_answers() {
+    let contents = "\
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+    assert_eq!(11, count_any(contents));
+    assert_eq!(6, count_all(contents));
+}
+
+#[test]
+fn test_bad_answers() {
+    use common::Solver;
+    assert_eq!(
+        Err(ParseError::new(3, 2, "B", "Questions are a to z")),
+        Day06::parse("abc\n\naBc")
+    );
+}
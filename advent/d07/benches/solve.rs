@@ -0,0 +1,55 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use d07::graph::BagGraph;
+use d07::BagSolver;
+
+// rules makes up count bag rules, each bag holding the next two, with shiny gold in the middle.
+fn rules(count: usize) -> String {
+    let name = |i: usize| {
+        if i == count / 2 {
+            "shiny gold".to_string()
+        } else {
+            format!("plain {}", i)
+        }
+    };
+
+    let mut rules = String::new();
+    for i in 0..count {
+        let inside: Vec<String> = (i + 1..count)
+            .take(2)
+            .enumerate()
+            .map(|(n, j)| format!("{} {} bags", n + 1, name(j)))
+            .collect();
+        if inside.is_empty() {
+            rules.push_str(&format!("{} bags contain no other bags.\n", name(i)));
+        } else {
+            rules.push_str(&format!("{} bags contain {}.\n", name(i), inside.join(", ")));
+        }
+    }
+    rules
+}
+
+fn bench_solve(c: &mut Criterion) {
+    let contents = rules(200);
+    let solver = BagSolver::new(&contents).unwrap();
+    c.bench_function("BagSolver::solve 200 bags", |b| b.iter(|| solver.solve("shiny gold")));
+}
+
+// bench_graph builds the graph each time round, since its answers are memoized. Everything inside
+// shiny gold is too many bags to count, so it counts a bag further down instead.
+fn bench_graph(c: &mut Criterion) {
+    let contents = rules(200);
+    let solver = BagSolver::new(&contents).unwrap();
+    let bags: Vec<_> = solver.bags().to_vec();
+    c.bench_function("BagGraph containers and total_inside, 200 bags", |b| {
+        b.iter(|| {
+            let graph = BagGraph::new(&bags);
+            (
+                graph.containers("shiny gold").unwrap().len(),
+                graph.total_inside("plain 150").unwrap(),
+            )
+        })
+    });
+}
+
+criterion_group!(benches, bench_solve, bench_graph);
+criterion_main!(benches);
@@ -0,0 +1,427 @@
+use crate::BagDescription;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::rc::Rc;
+
+// BagGraph is the bag rules as a graph: an edge from every bag to each bag it holds, and the same
+// edges the other way round, so questions in either direction only look at the bags that matter.
+// Answers are remembered, so asking again, or asking about a bag inside one already asked about,
+// is cheap.
+pub struct BagGraph<'a> {
+    colours: Vec<&'a str>,
+    ids: HashMap<&'a str, usize>,
+    // contents[bag] is every (bag, how many) directly inside bag.
+    contents: Vec<Vec<(usize, u64)>>,
+    // containers[bag] is every bag that directly holds bag.
+    containers: Vec<Vec<usize>>,
+    containers_memo: RefCell<HashMap<usize, Rc<Vec<usize>>>>,
+    inside_memo: RefCell<Vec<Option<u64>>>,
+}
+
+impl<'a> BagGraph<'a> {
+    // new builds the graph from parsed rules. A bag that's only ever inside others, without a rule
+    // of its own, holds nothing.
+    pub fn new(bags: &[BagDescription<'a>]) -> BagGraph<'a> {
+        let mut graph = BagGraph {
+            colours: Vec::new(),
+            ids: HashMap::new(),
+            contents: Vec::new(),
+            containers: Vec::new(),
+            containers_memo: RefCell::new(HashMap::new()),
+            inside_memo: RefCell::new(Vec::new()),
+        };
+        for bag in bags {
+            let outer = graph.add(bag.bag_adjective);
+            for rule in bag.fitted_rules.iter() {
+                // A "no other bags" rule is parsed as holding nothing.
+                if rule.amount <= 0 {
+                    continue;
+                }
+                let inner = graph.add(rule.adjective);
+                graph.contents[outer].push((inner, rule.amount as u64));
+                graph.containers[inner].push(outer);
+            }
+        }
+        graph.inside_memo = RefCell::new(vec![None; graph.colours.len()]);
+        graph
+    }
+
+    fn add(&mut self, colour: &'a str) -> usize {
+        if let Some(id) = self.ids.get(colour) {
+            return *id;
+        }
+        let id = self.colours.len();
+        self.colours.push(colour);
+        self.ids.insert(colour, id);
+        self.contents.push(Vec::new());
+        self.containers.push(Vec::new());
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.colours.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colours.is_empty()
+    }
+
+    // colours is every bag in the graph, in the order they first show up in the rules.
+    pub fn colours(&self) -> &[&'a str] {
+        &self.colours
+    }
+
+    fn id(&self, colour: &str) -> Result<usize, String> {
+        self.ids
+            .get(colour)
+            .copied()
+            .ok_or_else(|| format!("There are no {} bags", colour))
+    }
+
+    // contents is the bags directly inside colour, and how many of each.
+    pub fn contents(&self, colour: &str) -> Result<Vec<(&'a str, u64)>, String> {
+        let id = self.id(colour)?;
+        Ok(self.contents[id]
+            .iter()
+            .map(|(inner, amount)| (self.colours[*inner], *amount))
+            .collect())
+    }
+
+    // containers is every bag that can eventually hold a colour bag, sorted by colour.
+    pub fn containers(&self, colour: &str) -> Result<Vec<&'a str>, String> {
+        let ids = self.container_ids(self.id(colour)?);
+        let mut colours: Vec<&'a str> = ids.iter().map(|id| self.colours[*id]).collect();
+        colours.sort_unstable();
+        Ok(colours)
+    }
+
+    // can_contain is whether an outer bag can eventually hold an inner one.
+    pub fn can_contain(&self, outer: &str, inner: &str) -> Result<bool, String> {
+        let outer = self.id(outer)?;
+        Ok(self.container_ids(self.id(inner)?).contains(&outer))
+    }
+
+    fn container_ids(&self, id: usize) -> Rc<Vec<usize>> {
+        if let Some(found) = self.containers_memo.borrow().get(&id) {
+            return found.clone();
+        }
+
+        let mut seen = vec![false; self.len()];
+        let mut found = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(id);
+        while let Some(bag) = queue.pop_front() {
+            for &outer in self.containers[bag].iter() {
+                if !seen[outer] {
+                    seen[outer] = true;
+                    found.push(outer);
+                    queue.push_back(outer);
+                }
+            }
+        }
+
+        let found = Rc::new(found);
+        self.containers_memo.borrow_mut().insert(id, found.clone());
+        found
+    }
+
+    // path_between is the shortest way to nest an inner bag inside an outer one: every bag on the
+    // way in, after outer, with how many of it the bag before holds. None means inner never fits.
+    // inner can be outer, for a bag that ends up inside itself, and then the path ends on outer.
+    pub fn path_between(
+        &self,
+        outer: &str,
+        inner: &str,
+    ) -> Result<Option<Vec<(&'a str, u64)>>, String> {
+        let outer = self.id(outer)?;
+        let inner = self.id(inner)?;
+
+        // came_from[bag] is the bag that first led to bag, and how many of bag it holds.
+        let mut came_from: Vec<Option<(usize, u64)>> = vec![None; self.len()];
+        let mut queue = VecDeque::new();
+        queue.push_back(outer);
+        while let Some(bag) = queue.pop_front() {
+            for &(next, amount) in self.contents[bag].iter() {
+                if came_from[next].is_none() {
+                    came_from[next] = Some((bag, amount));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        // Walk back from inner until we're at outer again. outer only has a came_from when it's
+        // inside itself, so the walk has to stop there rather than when it runs out.
+        let mut path = Vec::new();
+        let mut bag = inner;
+        loop {
+            let (previous, amount) = match came_from[bag] {
+                Some(step) => step,
+                None => return Ok(None),
+            };
+            path.push((self.colours[bag], amount));
+            bag = previous;
+            if bag == outer {
+                break;
+            }
+        }
+        path.reverse();
+        Ok(Some(path))
+    }
+
+    // total_inside is how many bags a colour bag holds altogether, not counting itself. Bags
+    // that end up inside themselves would hold infinitely many, so that's an error, as is a count
+    // too big for a u64.
+    pub fn total_inside(&self, colour: &str) -> Result<u64, String> {
+        let id = self.id(colour)?;
+        if let Some(total) = self.inside_memo.borrow()[id] {
+            return Ok(total);
+        }
+        if let Some(cycle) = self.find_cycle_from(id) {
+            return Err(format!("Bags go round in a circle: {}", cycle.join(" -> ")));
+        }
+        self.inside(id)
+            .ok_or_else(|| format!("{} bags hold more bags than can be counted", colour))
+    }
+
+    // inside is total_inside for a bag that's known not to be in a cycle.
+    fn inside(&self, id: usize) -> Option<u64> {
+        if let Some(total) = self.inside_memo.borrow()[id] {
+            return Some(total);
+        }
+
+        let mut total: u64 = 0;
+        for &(inner, amount) in self.contents[id].iter() {
+            let each = self.inside(inner)?.checked_add(1)?;
+            total = total.checked_add(amount.checked_mul(each)?)?;
+        }
+        self.inside_memo.borrow_mut()[id] = Some(total);
+        Some(total)
+    }
+
+    // find_cycle is a list of bags that each hold the next, with the last holding the first
+    // again, if there's one anywhere in the rules.
+    pub fn find_cycle(&self) -> Option<Vec<&'a str>> {
+        let mut state = vec![Visit::New; self.len()];
+        (0..self.len()).find_map(|id| self.cycle_dfs(id, &mut state, &mut Vec::new()))
+    }
+
+    fn find_cycle_from(&self, id: usize) -> Option<Vec<&'a str>> {
+        let mut state = vec![Visit::New; self.len()];
+        self.cycle_dfs(id, &mut state, &mut Vec::new())
+    }
+
+    // cycle_dfs walks down from id. Any bag it gets back to while still inside it is a cycle.
+    fn cycle_dfs(
+        &self,
+        id: usize,
+        state: &mut Vec<Visit>,
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<&'a str>> {
+        match state[id] {
+            Visit::Done => return None,
+            Visit::Open => {
+                let start = path.iter().position(|p| *p == id).unwrap();
+                let mut cycle: Vec<&'a str> =
+                    path[start..].iter().map(|p| self.colours[*p]).collect();
+                cycle.push(self.colours[id]);
+                return Some(cycle);
+            }
+            Visit::New => {}
+        }
+
+        state[id] = Visit::Open;
+        path.push(id);
+        for &(inner, _) in self.contents[id].iter() {
+            if let Some(cycle) = self.cycle_dfs(inner, state, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        state[id] = Visit::Done;
+        None
+    }
+
+    // to_dot writes the graph for graphviz, with every edge labelled with how many bags it is.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        out.push_str("digraph bags {\n");
+        for (id, colour) in self.colours.iter().enumerate() {
+            writeln!(out, "    b{} [label=\"{}\"];", id, colour).unwrap();
+        }
+        for (outer, contents) in self.contents.iter().enumerate() {
+            for (inner, amount) in contents {
+                writeln!(out, "    b{} -> b{} [label=\"{}\"];", outer, inner, amount).unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    Open,
+    Done,
+}
+
+#[cfg(test)]
+fn example(rules: &str) -> Vec<BagDescription<'_>> {
+    rules
+        .lines()
+        .map(|line| crate::fitted_bag_parser(line).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+#[test]
+fn test_containers() {
+    let bags = example(EXAMPLE);
+    let graph = BagGraph::new(&bags);
+    assert_eq!(9, graph.len());
+    assert_eq!(
+        Ok(vec![
+            "bright white",
+            "dark orange",
+            "light red",
+            "muted yellow"
+        ]),
+        graph.containers("shiny gold")
+    );
+    // Asking again comes from the memo.
+    assert_eq!(Ok(4), graph.containers("shiny gold").map(|c| c.len()));
+    assert_eq!(Ok(7), graph.containers("faded blue").map(|c| c.len()));
+    assert_eq!(Ok(vec![]), graph.containers("light red"));
+
+    assert_eq!(Ok(true), graph.can_contain("light red", "dotted black"));
+    assert_eq!(Ok(false), graph.can_contain("dotted black", "light red"));
+    assert_eq!(
+        Err("There are no plaid bags".to_string()),
+        graph.containers("plaid")
+    );
+}
+
+#[test]
+fn test_total_inside() {
+    let bags = example(EXAMPLE);
+    let graph = BagGraph::new(&bags);
+    assert_eq!(Ok(32), graph.total_inside("shiny gold"));
+    assert_eq!(Ok(0), graph.total_inside("faded blue"));
+    assert_eq!(Ok(7), graph.total_inside("dark olive"));
+    assert_eq!(
+        Ok(vec![("dark olive", 1), ("vibrant plum", 2)]),
+        graph.contents("shiny gold")
+    );
+    assert_eq!(None, graph.find_cycle());
+}
+
+#[test]
+fn test_path_between() {
+    let bags = example(EXAMPLE);
+    let graph = BagGraph::new(&bags);
+    assert_eq!(
+        Ok(Some(vec![
+            ("bright white", 1),
+            ("shiny gold", 1),
+            ("dark olive", 1),
+            ("dotted black", 4)
+        ])),
+        graph.path_between("light red", "dotted black")
+    );
+    assert_eq!(Ok(None), graph.path_between("shiny gold", "light red"));
+    assert_eq!(Ok(None), graph.path_between("faded blue", "faded blue"));
+    assert_eq!(
+        Err("There are no plaid bags".to_string()),
+        graph.path_between("plaid", "shiny gold")
+    );
+}
+
+#[test]
+fn test_cycles() {
+    let bags = example(
+        "\
+light red bags contain 1 bright white bag.
+bright white bags contain 2 muted yellow bags, 1 faded blue bag.
+muted yellow bags contain 1 light red bag.
+faded blue bags contain no other bags.",
+    );
+    let graph = BagGraph::new(&bags);
+    assert_eq!(
+        Some(vec![
+            "light red",
+            "bright white",
+            "muted yellow",
+            "light red"
+        ]),
+        graph.find_cycle()
+    );
+    assert_eq!(
+        Err(
+            "Bags go round in a circle: bright white -> muted yellow -> light red -> bright white"
+                .to_string()
+        ),
+        graph.total_inside("bright white")
+    );
+    // A bag in the cycle is inside itself, by way of the rest of the cycle.
+    assert_eq!(
+        Ok(Some(vec![
+            ("bright white", 1),
+            ("muted yellow", 2),
+            ("light red", 1)
+        ])),
+        graph.path_between("light red", "light red")
+    );
+    assert_eq!(
+        Ok(Some(vec![("muted yellow", 2), ("light red", 1)])),
+        graph.path_between("bright white", "light red")
+    );
+    assert_eq!(Ok(None), graph.path_between("faded blue", "faded blue"));
+    // Bags outside the cycle can still be counted, and containers don't mind cycles.
+    assert_eq!(Ok(0), graph.total_inside("faded blue"));
+    assert_eq!(
+        Ok(vec!["bright white", "light red", "muted yellow"]),
+        graph.containers("light red")
+    );
+}
+
+#[test]
+fn test_overflow() {
+    // Each bag holds a million of the next, which is more bags than a u64 can count by b0.
+    let mut rules = String::new();
+    for i in 0..4 {
+        rules.push_str(&format!("b{} bags contain 1000000 b{} bags.\n", i, i + 1));
+    }
+    let bags = example(&rules);
+    let graph = BagGraph::new(&bags);
+    assert_eq!(Ok(1_000_001_000_000), graph.total_inside("b2"));
+    assert_eq!(
+        Err("b0 bags hold more bags than can be counted".to_string()),
+        graph.total_inside("b0")
+    );
+}
+
+#[test]
+fn test_to_dot() {
+    let bags =
+        example("shiny gold bags contain 2 dark red bags.\ndark red bags contain no other bags.");
+    let expected = "\
+digraph bags {
+    b0 [label=\"shiny gold\"];
+    b1 [label=\"dark red\"];
+    b0 -> b1 [label=\"2\"];
+}
+";
+    assert_eq!(expected, BagGraph::new(&bags).to_dot());
+}
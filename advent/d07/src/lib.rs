@@ -0,0 +1,271 @@
+extern crate nom;
+
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_until};
+use nom::character::complete::{char, digit1};
+use nom::error::ErrorKind;
+use nom::lib::std::collections::HashMap;
+use nom::{multi::separated_list1, sequence::tuple, IResult};
+use common::ParseError;
+use graph::BagGraph;
+use std::convert::TryFrom;
+
+pub mod graph;
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct BagRule<'a> {
+    pub amount: i32,
+    pub adjective: &'a str,
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct BagDescription<'a> {
+    pub bag_adjective: &'a str,
+    pub fitted_rules: Vec<BagRule<'a>>,
+}
+
+// BagSolver is the bag rules, checked so every bag has a rule. BagGraph does the traversing.
+pub struct BagSolver<'a> {
+    bags: Vec<BagDescription<'a>>,
+}
+
+impl<'a> BagSolver<'a> {
+    pub fn new(content: &str) -> Result<BagSolver<'_>, ParseError> {
+        let mut hash = HashMap::new();
+        let mut vecs = Vec::new();
+        for line in content.lines() {
+            let bag = match fitted_bag_parser(line) {
+                Ok(b) => b,
+                Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+                    return Err(ParseError::at(content, e.input, "Could not parse bag rule"))
+                }
+                Err(nom::Err::Incomplete(_)) => {
+                    return Err(ParseError::at(content, line, "Could not parse bag rule"))
+                }
+            };
+            vecs.push(bag.clone());
+            hash.insert(bag.bag_adjective, bag);
+        }
+
+        // Every bag that fits in another has to have rules of its own, or we'd get lost solving.
+        for bag in vecs.iter() {
+            for rule in bag.fitted_rules.iter() {
+                if !hash.contains_key(rule.adjective) {
+                    return Err(ParseError::at(content, rule.adjective, "No rule for bag"));
+                }
+            }
+        }
+
+        Ok(BagSolver { bags: vecs })
+    }
+
+    // bags is every rule, in the order they were written.
+    pub fn bags(&self) -> &[BagDescription<'a>] {
+        &self.bags
+    }
+
+    // solve counts the bags that can eventually hold a target bag, apart from the target itself
+    // when it's in a circle of bags.
+    pub fn solve(&self, target: &str) -> Result<u32, String> {
+        let containers = BagGraph::new(&self.bags).containers(target)?;
+        let count = containers.iter().filter(|colour| **colour != target).count();
+        u32::try_from(count).map_err(|_| format!("Too many bags hold {} bags to count", target))
+    }
+
+    // solve_reverse counts a target bag and every bag inside it. It leaves the counting to
+    // BagGraph, which notices bags that end up inside themselves rather than counting forever.
+    pub fn solve_reverse(&self, target: &str) -> Result<i32, String> {
+        let total = BagGraph::new(&self.bags).total_inside(target)?;
+        total
+            .checked_add(1)
+            .and_then(|all| i32::try_from(all).ok())
+            .ok_or_else(|| format!("{} bags hold more bags than can be counted", target))
+    }
+}
+
+// fitted_bag_parser will parse a string of the form:
+// <adjectives> (bag|bags) contain<LOOP>
+// where loop is of the form " <adjectives> (bag|bags)" with a comma between them.
+pub fn fitted_bag_parser(i: &str) -> Result<BagDescription, nom::Err<nom::error::Error<&str>>> {
+    let mut b = tuple((
+        // In the word "light red bags contains", we want to capture "light red", and consume up to and including contains
+        take_until(" bag"),
+        alt((tag(" bags"), tag(" bag"))),
+        take_until("contain"),
+        tag("contain"),
+        // At this point, we start the loop of "<space><adjectives> (bag|bags)", separated by commas
+        separated_list1(tag(","), parse_descriptors),
+    ));
+
+    if let Err(h) = b(i) {
+        return Err(h);
+    }
+
+    let (_, (origin, _, _, _, last)) = b(i)?;
+
+    let mut all_rules = Vec::new();
+    for rule in last.iter() {
+        if let Some(x) = rule {
+            all_rules.push(x.clone());
+        }
+    }
+    return Ok(BagDescription {
+        bag_adjective: origin,
+        fitted_rules: all_rules,
+    });
+}
+
+// Descriptors will be broken apart by an overarching separated list, and will be of the form:
+//  <space><multiple_adjectives><"bag"|"bags">
+// the bag|bags has a preceding space and so we will consume that token as well
+fn parse_descriptors(i: &str) -> IResult<&str, Option<BagRule>> {
+    if i == " no other bags." {
+        return Ok(("", None));
+    }
+
+    let mut b = tuple((
+        char(' '),
+        alt((tag("no other"), digit1)),
+        char(' '),
+        take_until(" bag"),
+        alt((tag(" bags"), tag(" bag"))),
+    ));
+    let (s, (_, quantity, _, adj, _)) = b(i)?;
+    let quantity: i32 = match quantity {
+        "no other" => 0,
+        _ => match quantity.parse() {
+            Ok(quantity) => quantity,
+            Err(_) => {
+                return Err(nom::Err::Failure(nom::error::make_error(
+                    s,
+                    ErrorKind::Digit,
+                )))
+            }
+        },
+    };
+
+    Ok((
+        s,
+        Some(BagRule {
+            amount: quantity,
+            adjective: adj,
+        }),
+    ))
+}
+
+#[test]
+fn bag_parser() {
+    assert_eq!(
+        BagDescription {
+            bag_adjective: "light red",
+            fitted_rules: vec![
+                BagRule {
+                    amount: 1,
+                    adjective: "bright white"
+                },
+                BagRule {
+                    amount: 2,
+                    adjective: "muted yellow"
+                }
+            ]
+        },
+        fitted_bag_parser("light red bags contain 1 bright white bag, 2 muted yellow bags.")
+            .unwrap()
+    );
+}
+
+// Day07 is the luggage rules.
+pub struct Day07;
+
+impl common::Solver for Day07 {
+    type Input<'a> = BagGraph<'a>;
+
+    fn parse(contents: &str) -> Result<BagGraph<'_>, ParseError> {
+        Ok(BagGraph::new(&BagSolver::new(contents)?.bags))
+    }
+
+    // part1 counts how many bags can eventually hold a shiny gold bag.
+    fn part1(graph: &BagGraph) -> Result<String, String> {
+        Ok(graph.containers("shiny gold")?.len().to_string())
+    }
+
+    // part2 counts the bags inside a shiny gold bag.
+    fn part2(graph: &BagGraph) -> Result<String, String> {
+        Ok(graph.total_inside("shiny gold")?.to_string())
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day07>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day07>(contents)
+}
+
+#[test]
+fn test_example_rules() {
+    let contents = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+    assert_eq!(Ok("4".to_string()), part1(contents));
+    assert_eq!(Ok("32".to_string()), part2(contents));
+}
+
+#[test]
+fn test_any_bag() {
+    let contents = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain no other bags.
+faded blue bags contain no other bags.";
+    let solver = BagSolver::new(contents).unwrap();
+    assert_eq!(Ok(3), solver.solve("shiny gold"));
+    assert_eq!(Ok(2), solver.solve("faded blue"));
+    // solve_reverse counts the muted yellow bag too.
+    assert_eq!(Ok(12), solver.solve_reverse("muted yellow"));
+    assert_eq!(Err("There are no plaid bags".to_string()), solver.solve("plaid"));
+    assert_eq!(Err("There are no plaid bags".to_string()), solver.solve_reverse("plaid"));
+}
+
+#[test]
+fn test_reverse_cycle() {
+    let contents = "\
+light red bags contain 1 bright white bag.
+bright white bags contain 1 light red bag, 2 faded blue bags.
+faded blue bags contain no other bags.";
+    let solver = BagSolver::new(contents).unwrap();
+    assert_eq!(
+        Err("Bags go round in a circle: light red -> bright white -> light red".to_string()),
+        solver.solve_reverse("light red")
+    );
+    assert_eq!(Ok(1), solver.solve_reverse("faded blue"));
+    assert_eq!(Ok(2), solver.solve("faded blue"));
+    // light red can hold itself through bright white, but only bright white counts.
+    assert_eq!(Ok(1), solver.solve("light red"));
+}
+
+#[test]
+fn test_bad_rules() {
+    let contents = "\
+light red bags contain 1 bright white bag.
+bright white bags hold 1 shiny gold bag.";
+    let err = BagSolver::new(contents).err().unwrap();
+    assert_eq!((2, "Could not parse bag rule"), (err.line, &err.message[..]));
+
+    let contents = "\
+light red bags contain 1 bright white bag.
+bright white bags contain 2 shiny gold bags.";
+    assert_eq!(
+        Some(ParseError::new(2, 29, "shiny gold", "No rule for bag")),
+        BagSolver::new(contents).err()
+    );
+}
@@ -1,240 +1,276 @@
-extern crate nom;
-
-use clap::{App, Arg};
-use nom::branch::alt;
-use nom::bytes::complete::{tag, take_until};
-use nom::character::complete::{char, digit1};
-use nom::error::ErrorKind;
-use nom::lib::std::collections::HashMap;
-use nom::{multi::separated_list1, sequence::tuple, IResult};
+use clap::{App, Arg, ArgMatches};
+use d07::graph::BagGraph;
+use d07::BagSolver;
 use std::fs;
+use std::process;
 
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
-struct BagRule<'a> {
-    amount: i32,
-    adjective: &'a str,
-}
-
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
-struct BagDescription<'a> {
-    bag_adjective: &'a str,
-    fitted_rules: Vec<BagRule<'a>>,
+// app is the command line: a question about the bag rules, then the rules.
+fn app() -> App<'static> {
+    let input = || {
+        Arg::new("INPUT")
+            .help("Sets the input file to use")
+            .required(true)
+    };
+    let bag = || {
+        Arg::new("bag")
+            .long("bag")
+            .takes_value(true)
+            .value_name("colour")
+            .help("Asks about this bag instead of shiny gold")
+    };
+    App::new("Reader for advent")
+        .version("1.0")
+        .author("Sean Tyler Myers <seanmyers0608@gmail.com>")
+        .about("Answers questions about bag rules")
+        .subcommand_required(true)
+        .subcommand(
+            App::new("containers")
+                .about("Lists every bag that can eventually hold the bag")
+                .arg(bag())
+                .arg(input().index(1)),
+        )
+        .subcommand(
+            App::new("contents")
+                .about("Lists what's inside the bag, and counts every bag it holds")
+                .arg(bag())
+                .arg(input().index(1)),
+        )
+        .subcommand(
+            App::new("path-between")
+                .about("Shows how to fit the inner bag inside the outer one")
+                .arg(Arg::new("OUTER").required(true).index(1))
+                .arg(Arg::new("INNER").required(true).index(2))
+                .arg(input().index(3)),
+        )
+        .subcommand(
+            App::new("dot")
+                .about("Prints the rules as a graph for graphviz")
+                .arg(input().index(1)),
+        )
 }
 
-// Bag traversal is gonna be extremely slow, but that's alright.
-struct BagSolver<'a> {
-    bags: Vec<BagDescription<'a>>,
-    bags_arranged: HashMap<&'a str, BagDescription<'a>>,
+// Query is the question to ask about the bag rules.
+#[derive(Debug, PartialEq)]
+enum Query {
+    // Every bag that can eventually hold the bag.
+    Containers,
+    // What's directly inside the bag, and how many bags it holds altogether.
+    Contents,
+    // How to fit the second bag inside the first.
+    PathBetween(String, String),
+    // The whole graph, for graphviz.
+    Dot,
 }
 
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
-struct InternalBagCounter<'a> {
-    bag: BagDescription<'a>,
-    count: i32,
+// Config is what to ask, about which bag, and where the rules are. The bag is shiny gold unless
+// --bag says otherwise.
+#[derive(Debug, PartialEq)]
+struct Config {
+    query: Query,
+    bag: String,
+    filename: String,
 }
 
-struct Checkpoint<'a> {
-    bags: Vec<BagDescription<'a>>,
-    traversed: HashMap<&'a str, bool>,
-}
+impl Config {
+    fn new(matches: &ArgMatches) -> Config {
+        let (name, sub) = matches.subcommand().unwrap();
+        let query = match name {
+            "containers" => Query::Containers,
+            "contents" => Query::Contents,
+            "path-between" => Query::PathBetween(
+                sub.value_of("OUTER").unwrap().to_string(),
+                sub.value_of("INNER").unwrap().to_string(),
+            ),
+            _ => Query::Dot,
+        };
+        let bag = match query {
+            Query::Containers | Query::Contents => sub.value_of("bag"),
+            _ => None,
+        };
 
-impl<'a> BagSolver<'a> {
-    fn new(content: &str) -> Result<BagSolver, &str> {
-        let mut hash = HashMap::new();
-        let mut vecs = Vec::new();
-        for line in content.lines() {
-            let bag = match fitted_bag_parser(line) {
-                Ok(b) => b,
-                Err(_e) => return Err(line),
-            };
-            vecs.push(bag.clone());
-            hash.insert(bag.bag_adjective, bag);
+        Config {
+            query,
+            bag: bag.unwrap_or("shiny gold").to_string(),
+            filename: sub.value_of("INPUT").unwrap().to_string(),
         }
-
-        return Ok(BagSolver {
-            bags: vecs,
-            bags_arranged: hash,
-        });
     }
+}
 
-    fn solve(&self) -> u32 {
-        let mut shines = 0;
-        for bag in self.bags.clone() {
-            let mut checkpoint = Checkpoint {
-                bags: Vec::new(),
-                traversed: HashMap::new(),
-            };
-            checkpoint.bags.push(bag.clone());
-            checkpoint.traversed.insert(bag.bag_adjective, true);
-
-            loop {
-                if checkpoint.bags.len() == 0 {
-                    break;
-                }
-
-                let loop_bag = checkpoint.bags.pop().unwrap();
-                for rule in loop_bag.fitted_rules {
-                    let fitted_bag = self.bags_arranged.get(rule.adjective).unwrap();
-
-                    if !checkpoint.traversed.contains_key(fitted_bag.bag_adjective) {
-                        checkpoint.bags.push(fitted_bag.clone());
-                    }
-                }
-
-                checkpoint.traversed.insert(loop_bag.bag_adjective, true);
-            }
+fn bags(amount: u64, colour: &str) -> String {
+    if amount == 1 {
+        format!("1 {} bag", colour)
+    } else {
+        format!("{} {} bags", amount, colour)
+    }
+}
 
-            if checkpoint.traversed.contains_key("shiny gold") && bag.bag_adjective != "shiny gold"
-            {
-                println!("{}", bag.bag_adjective);
-                shines += 1;
+// answer asks graph the question in config, and says what it found.
+fn answer(config: &Config, graph: &BagGraph) -> Result<String, String> {
+    let mut out = String::new();
+    match &config.query {
+        Query::Containers => {
+            let containers = graph.containers(&config.bag)?;
+            out.push_str(&format!(
+                "{} can hold a {} bag\n",
+                bags(containers.len() as u64, "different"),
+                config.bag
+            ));
+            for colour in containers {
+                out.push_str(&format!("  {}\n", colour));
             }
         }
-
-        return shines;
-    }
-    fn solve_reverse(&self) -> i32 {
-        let mut all_bags: i32 = 0;
-        let mut traversed = HashMap::new();
-        let mut bags = Vec::new();
-
-        let shiny = self.bags_arranged.get("shiny gold").unwrap();
-
-        bags.push(InternalBagCounter {
-            bag: shiny.clone(),
-            count: 1,
-        });
-        loop {
-            if bags.len() == 0 {
-                break;
+        Query::Contents => {
+            let total = graph.total_inside(&config.bag)?;
+            out.push_str(&format!(
+                "A {} bag holds {} altogether\n",
+                config.bag,
+                bags(total, "other")
+            ));
+            for (colour, amount) in graph.contents(&config.bag)? {
+                out.push_str(&format!("  {}\n", bags(amount, colour)));
             }
-
-            let current = bags.pop().unwrap();
-            let current_adjective = current.bag.bag_adjective;
-            all_bags += current.count;
-            for rule in current.bag.fitted_rules {
-                let rule_bag = self.bags_arranged.get(rule.adjective).unwrap();
-                bags.push(InternalBagCounter {
-                    bag: rule_bag.clone(),
-                    count: current.count * rule.amount,
-                })
-            }
-            traversed.insert(current_adjective, true);
         }
-        return all_bags;
+        Query::PathBetween(outer, inner) => match graph.path_between(outer, inner)? {
+            Some(path) => {
+                out.push_str(&format!("A {} bag", outer));
+                for (i, (colour, amount)) in path.iter().enumerate() {
+                    let which = if i == 0 { " holds" } else { ",\n  which holds" };
+                    out.push_str(&format!("{} {}", which, bags(*amount, colour)));
+                }
+                out.push_str(".\n");
+            }
+            None => return Err(format!("A {} bag can't hold a {} bag", outer, inner)),
+        },
+        Query::Dot => out.push_str(&graph.to_dot()),
     }
+    Ok(out)
 }
 
-// fitted_bag_parser will parse a string of the form:
-// <adjectives> (bag|bags) contain<LOOP>
-// where loop is of the form " <adjectives> (bag|bags)" with a comma between them.
-fn fitted_bag_parser(i: &str) -> Result<BagDescription, nom::Err<nom::error::Error<&str>>> {
-    let mut b = tuple((
-        // In the word "light red bags contains", we want to capture "light red", and consume up to and including contains
-        take_until(" bag"),
-        alt((tag(" bags"), tag(" bag"))),
-        take_until("contain"),
-        tag("contain"),
-        // At this point, we start the loop of "<space><adjectives> (bag|bags)", separated by commas
-        separated_list1(tag(","), parse_descriptors),
-    ));
-
-    if let Err(h) = b(i) {
-        return Err(h);
-    }
+fn main() {
+    let config = Config::new(&app().get_matches());
 
-    let (_, (origin, _, _, _, last)) = b(i)?;
+    let contents = fs::read_to_string(&config.filename).unwrap_or_else(|err| {
+        eprintln!("Problem reading {}: {}", config.filename, err);
+        process::exit(1);
+    });
+    let solver = BagSolver::new(&contents).unwrap_or_else(|err| {
+        eprintln!("Problem parsing {}", err.in_file(&config.filename));
+        process::exit(1);
+    });
 
-    let mut all_rules = Vec::new();
-    for rule in last.iter() {
-        if let Some(x) = rule {
-            all_rules.push(x.clone());
+    match answer(&config, &BagGraph::new(solver.bags())) {
+        Ok(out) => print!("{}", out),
+        Err(err) => {
+            eprintln!("Problem answering: {}", err);
+            process::exit(1);
         }
     }
-    return Ok(BagDescription {
-        bag_adjective: origin,
-        fitted_rules: all_rules,
-    });
 }
 
-// Descriptors will be broken apart by an overarching separated list, and will be of the form:
-//  <space><multiple_adjectives><"bag"|"bags">
-// the bag|bags has a preceding space and so we will consume that token as well
-fn parse_descriptors(i: &str) -> IResult<&str, Option<BagRule>> {
-    if i == " no other bags." {
-        return Ok(("", None));
-    }
+#[cfg(test)]
+fn config(args: &[&str]) -> Result<Config, String> {
+    app()
+        .try_get_matches_from(std::iter::once("d07").chain(args.iter().copied()))
+        .map(|matches| Config::new(&matches))
+        .map_err(|err| err.to_string())
+}
 
-    let mut b = tuple((
-        char(' '),
-        alt((tag("no other"), digit1)),
-        char(' '),
-        take_until(" bag"),
-        alt((tag(" bags"), tag(" bag"))),
-    ));
-    let (s, (_, quantity, _, adj, _)) = b(i)?;
-    let quantity: i32 = match quantity {
-        "no other" => 0,
-        _ => match quantity.parse() {
-            Ok(quantity) => quantity,
-            Err(_) => {
-                return Err(nom::Err::Failure(nom::error::make_error(
-                    s,
-                    ErrorKind::Digit,
-                )))
-            }
-        },
-    };
+#[cfg(test)]
+const EXAMPLE: &str = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
 
-    Ok((
-        s,
-        Some(BagRule {
-            amount: quantity,
-            adjective: adj,
-        }),
-    ))
+#[cfg(test)]
+fn ask(command: &[&str]) -> Result<String, String> {
+    let config = config(command)?;
+    let solver = BagSolver::new(EXAMPLE).unwrap();
+    answer(&config, &BagGraph::new(solver.bags()))
 }
 
 #[test]
-fn bag_parser() {
+fn test_config() {
     assert_eq!(
-        BagDescription {
-            bag_adjective: "light red",
-            fitted_rules: vec![
-                BagRule {
-                    amount: 1,
-                    adjective: "bright white"
-                },
-                BagRule {
-                    amount: 2,
-                    adjective: "muted yellow"
-                }
-            ]
-        },
-        fitted_bag_parser("light red bags contain 1 bright white bag, 2 muted yellow bags.")
-            .unwrap()
+        Ok(Config {
+            query: Query::Containers,
+            bag: "shiny gold".to_string(),
+            filename: "rules.txt".to_string()
+        }),
+        config(&["containers", "rules.txt"])
+    );
+    assert_eq!(
+        Ok(Config {
+            query: Query::Contents,
+            bag: "dark olive".to_string(),
+            filename: "rules.txt".to_string()
+        }),
+        config(&["contents", "--bag", "dark olive", "rules.txt"])
+    );
+    assert_eq!(
+        Ok("faded blue".to_string()),
+        config(&["containers", "rules.txt", "--bag=faded blue"]).map(|c| c.bag)
+    );
+    assert_eq!(
+        Ok(Query::PathBetween(
+            "light red".to_string(),
+            "dotted black".to_string()
+        )),
+        config(&["path-between", "light red", "dotted black", "rules.txt"]).map(|c| c.query)
     );
-}
 
-fn main() {
-    let matches = App::new("Reader for advent")
-        .version("1.0")
-        .author("Sean Tyler Myers <seanmyers0608@gmail.com>")
-        .about("Reads advent calendar programs")
-        .arg(
-            Arg::new("INPUT")
-                .about("Sets the input file to use")
-                .required(true)
-                .index(1),
-        )
-        .get_matches();
+    assert!(config(&["rules.txt"]).is_err());
+    assert!(config(&["dot", "--bag", "red", "rules.txt"]).is_err());
+    assert!(config(&["containers", "rules.txt", "--bag"]).is_err());
+}
 
-    let txt_location: &str = matches.value_of("INPUT").unwrap();
-    let contents = fs::read_to_string(txt_location).unwrap();
+#[test]
+fn test_answers() {
+    assert_eq!(
+        Ok("4 different bags can hold a shiny gold bag
+  bright white
+  dark orange
+  light red
+  muted yellow
+"
+        .to_string()),
+        ask(&["containers", "rules.txt"])
+    );
+    assert_eq!(
+        Ok("A dark olive bag holds 7 other bags altogether
+  3 faded blue bags
+  4 dotted black bags
+"
+        .to_string()),
+        ask(&["contents", "--bag", "dark olive", "rules.txt"])
+    );
+    assert_eq!(
+        Ok("A light red bag holds 1 bright white bag,
+  which holds 1 shiny gold bag,
+  which holds 2 vibrant plum bags.
+"
+        .to_string()),
+        ask(&["path-between", "light red", "vibrant plum", "rules.txt"])
+    );
+}
 
-    let solver = BagSolver::new(&contents).unwrap();
-    let first = solver.solve();
-    println!("{:?}", first);
-    let result = solver.solve_reverse();
-    println!("{:?}", result-1);
+#[test]
+fn test_unknown_bags() {
+    assert_eq!(
+        Err("There are no plaid bags".to_string()),
+        ask(&["containers", "--bag", "plaid", "rules.txt"])
+    );
+    assert_eq!(
+        Err("There are no plaid bags".to_string()),
+        ask(&["path-between", "light red", "plaid", "rules.txt"])
+    );
+    assert_eq!(
+        Err("A shiny gold bag can't hold a light red bag".to_string()),
+        ask(&["path-between", "shiny gold", "light red", "rules.txt"])
+    );
 }
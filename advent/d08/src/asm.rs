@@ -0,0 +1,240 @@
+use crate::vm;
+use crate::{Instruction, Program};
+use common::ParseError;
+use std::collections::{BTreeSet, HashMap};
+
+// assemble reads a program written in the puzzle's format, plus:
+//   labels     "loop: acc +1" or "loop:" on a line of its own, naming the next instruction
+//   targets    "jmp loop" or "nop loop" instead of counting the offset by hand
+//   comments   anything after # or ;
+//   blank lines, and a missing newline at the end
+// A label after the last instruction names the end of the program, so "jmp end" halts.
+pub fn assemble(contents: &str) -> Result<Program, ParseError> {
+    let mut labels: HashMap<&str, usize> = HashMap::new();
+    // (operation, argument) for every instruction, in order.
+    let mut lines: Vec<(&str, &str)> = Vec::new();
+
+    for line in contents.lines() {
+        let mut code = match line.find(['#', ';']) {
+            Some(i) => &line[..i],
+            None => line,
+        };
+
+        if let Some(colon) = code.find(':') {
+            let label = code[..colon].trim();
+            if !is_label(label) {
+                return Err(ParseError::at(contents, &code[..colon], "Not a label name"));
+            }
+            if labels.insert(label, lines.len()).is_some() {
+                return Err(ParseError::at(contents, label, "Label is already defined"));
+            }
+            code = &code[colon + 1..];
+        }
+
+        let words: Vec<&str> = code.split_whitespace().collect();
+        match words.as_slice() {
+            [] => continue,
+            [operation, argument] => lines.push((operation, argument)),
+            [operation] => {
+                return Err(ParseError::at(
+                    contents,
+                    operation,
+                    "Instruction has no argument",
+                ))
+            }
+            [_, _, extra, ..] => {
+                return Err(ParseError::at(
+                    contents,
+                    extra,
+                    "Unexpected text after instruction",
+                ))
+            }
+        }
+    }
+
+    let mut instructions = Vec::new();
+    for (pointer, (operation, argument)) in lines.into_iter().enumerate() {
+        let op = vm::opcode(operation)
+            .ok_or_else(|| ParseError::at(contents, operation, "Unknown instruction"))?;
+
+        let argument = if argument.starts_with(|c: char| c == '+' || c == '-' || c.is_ascii_digit())
+        {
+            argument
+                .parse::<i32>()
+                .map_err(|_| ParseError::at(contents, argument, "Could not parse number"))?
+        } else if op.name == "acc" {
+            return Err(ParseError::at(
+                contents,
+                argument,
+                "Only jmp and nop take a label",
+            ));
+        } else {
+            match labels.get(argument) {
+                Some(target) => *target as i32 - pointer as i32,
+                None => return Err(ParseError::at(contents, argument, "No such label")),
+            }
+        };
+        instructions.push((op.build)(argument));
+    }
+
+    if instructions.is_empty() {
+        return Err(ParseError::at(
+            contents,
+            contents,
+            "Program has no instructions",
+        ));
+    }
+    Ok(Program { instructions })
+}
+
+fn is_label(label: &str) -> bool {
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// disassemble prints program back out as assembly, one instruction per line with its number in a
+// comment. Every instruction a jmp lands on gets a label, and every jmp says where it lands. What
+// it prints assembles back into the same program.
+pub fn disassemble(program: &Program) -> String {
+    let len = program.instructions.len();
+    let targets: BTreeSet<usize> = program
+        .instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, ins)| matches!(ins, Instruction::Jmp(_)))
+        .map(|(pointer, ins)| ins.successor(pointer))
+        .filter(|target| *target >= 0 && *target <= len as i64)
+        .map(|target| target as usize)
+        .collect();
+    let label = |target: usize| {
+        if target == len {
+            "end".to_string()
+        } else {
+            format!("l{}", target)
+        }
+    };
+
+    let mut out = String::new();
+    for (pointer, ins) in program.instructions.iter().enumerate() {
+        let name = if targets.contains(&pointer) {
+            format!("{}:", label(pointer))
+        } else {
+            String::new()
+        };
+        let mut note = format!("{}", pointer);
+        if let Instruction::Jmp(_) = ins {
+            let target = ins.successor(pointer);
+            if target >= 0 && target <= len as i64 {
+                note.push_str(&format!(", to {}", label(target as usize)));
+            } else {
+                note.push_str(&format!(", to {} outside the program", target));
+            }
+        }
+        out.push_str(&format!("{:<7}{:<10}; {}\n", name, ins.to_string(), note));
+    }
+    if targets.contains(&len) {
+        out.push_str(&format!("{}:\n", label(len)));
+    }
+    out
+}
+
+#[test]
+fn test_assemble() {
+    let contents = "\
+# The example program, with labels.
+start:  nop +0
+again:  acc +1      ; count
+        jmp skip
+back:   acc +3
+        jmp again
+        acc -99
+skip:   acc +1
+        jmp back
+        acc +6";
+    let example = crate::parse_program(
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n",
+    )
+    .unwrap();
+    assert_eq!(Ok(example), assemble(contents));
+}
+
+#[test]
+fn test_jump_to_end() {
+    let program = assemble("jmp done\nacc +1\ndone:").unwrap();
+    assert_eq!(
+        vec![Instruction::Jmp(2), Instruction::Acc(1)],
+        program.instructions
+    );
+}
+
+#[test]
+fn test_assemble_errors() {
+    let cases = [
+        (
+            "acc +1\nmul +2",
+            ParseError::new(2, 1, "mul", "Unknown instruction"),
+        ),
+        (
+            "jmp nowhere",
+            ParseError::new(1, 5, "nowhere", "No such label"),
+        ),
+        (
+            "a: nop +0\na: nop +0",
+            ParseError::new(2, 1, "a", "Label is already defined"),
+        ),
+        (
+            "acc a\na: nop +0",
+            ParseError::new(1, 5, "a", "Only jmp and nop take a label"),
+        ),
+        (
+            "acc +1 +2",
+            ParseError::new(1, 8, "+2", "Unexpected text after instruction"),
+        ),
+        (
+            "acc\n",
+            ParseError::new(1, 1, "acc", "Instruction has no argument"),
+        ),
+        (
+            "9x: acc +1",
+            ParseError::new(1, 1, "9x", "Not a label name"),
+        ),
+        (
+            "acc +1x",
+            ParseError::new(1, 5, "+1x", "Could not parse number"),
+        ),
+        (
+            "# nothing",
+            ParseError::new(1, 1, "# nothing", "Program has no instructions"),
+        ),
+    ];
+    for (contents, err) in cases.iter() {
+        assert_eq!(Err(err.clone()), assemble(contents), "{}", contents);
+    }
+}
+
+#[test]
+fn test_disassemble() {
+    let program = assemble(
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\njmp +2\njmp -99",
+    )
+    .unwrap();
+    // The first line starts with spaces, which a \ at the end of the line would swallow.
+    let expected = "       nop +0    ; 0
+l1:    acc +1    ; 1
+       jmp +4    ; 2, to l6
+l3:    acc +3    ; 3
+       jmp -3    ; 4, to l1
+       acc -99   ; 5
+l6:    acc +1    ; 6
+       jmp -4    ; 7, to l3
+       jmp +2    ; 8, to end
+       jmp -99   ; 9, to -90 outside the program
+end:
+";
+    assert_eq!(expected, disassemble(&program));
+    assert_eq!(Ok(program.clone()), assemble(&disassemble(&program)));
+}
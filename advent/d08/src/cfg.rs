@@ -0,0 +1,240 @@
+use crate::Program;
+use std::fmt::Write;
+
+// Exit is how running a program from the start ends, worked out without running it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Exit {
+    // The program runs off the end, like it's meant to.
+    Halts,
+    // The program jumps somewhere outside itself from the given instruction.
+    Escapes(usize),
+    // The program goes back to the given instruction and goes round forever.
+    Loops(usize),
+}
+
+// Analysis is what can be told about a program from its control flow graph. Every instruction
+// has exactly one successor, so the graph is a set of chains, some ending in cycles.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Analysis {
+    // reachable[n] is whether instruction n ever runs.
+    pub reachable: Vec<bool>,
+    // Every cycle in the graph, each listed from its lowest instruction in the order they run.
+    pub loops: Vec<Vec<usize>>,
+    pub exit: Exit,
+}
+
+impl Analysis {
+    pub fn new(program: &Program) -> Analysis {
+        let len = program.instructions.len();
+        let next = |n: usize| program.instructions[n].successor(n);
+
+        let mut reachable = vec![false; len];
+        let mut pointer = 0;
+        let exit = loop {
+            if pointer == len {
+                break Exit::Halts;
+            }
+            if reachable[pointer] {
+                break Exit::Loops(pointer);
+            }
+            reachable[pointer] = true;
+            let n = next(pointer);
+            if n < 0 || n > len as i64 {
+                break Exit::Escapes(pointer);
+            }
+            pointer = n as usize;
+        };
+
+        // Walk from every instruction not seen yet. A walk that runs into itself has found a
+        // cycle; one that runs into an earlier walk, or off the program, hasn't.
+        let mut walked = vec![0; len];
+        let mut loops = Vec::new();
+        for start in 0..len {
+            let mut pointer = start as i64;
+            let found = loop {
+                if pointer < 0 || pointer >= len as i64 {
+                    break None;
+                }
+                let p = pointer as usize;
+                if walked[p] == start + 1 {
+                    break Some(p);
+                }
+                if walked[p] != 0 {
+                    break None;
+                }
+                walked[p] = start + 1;
+                pointer = next(p);
+            };
+            if let Some(pointer) = found {
+                let mut cycle = vec![pointer];
+                let mut n = next(pointer) as usize;
+                while n != pointer {
+                    cycle.push(n);
+                    n = next(n) as usize;
+                }
+                let lowest = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap();
+                cycle.rotate_left(lowest);
+                loops.push(cycle);
+            }
+        }
+        loops.sort();
+
+        Analysis {
+            reachable,
+            loops,
+            exit,
+        }
+    }
+
+    // unreachable is every instruction that never runs.
+    pub fn unreachable(&self) -> Vec<usize> {
+        (0..self.reachable.len())
+            .filter(|n| !self.reachable[*n])
+            .collect()
+    }
+
+    // warnings describes everything wrong with the program, or nothing if it halts cleanly and
+    // runs every instruction.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        match self.exit {
+            Exit::Halts => {}
+            Exit::Escapes(n) => warnings.push(format!("jmp on {} leaves the program", n)),
+            Exit::Loops(n) => warnings.push(format!("infinite loop, {} runs twice", n)),
+        }
+        for cycle in self.loops.iter() {
+            if !self.reachable[cycle[0]] {
+                warnings.push(format!("unreachable infinite loop through {:?}", cycle));
+            }
+        }
+        let unreachable = self.unreachable();
+        if !unreachable.is_empty() {
+            warnings.push(format!("unreachable instructions {:?}", unreachable));
+        }
+        warnings
+    }
+}
+
+// to_dot writes program's control flow graph for graphviz. Instructions that never run are
+// grey and dashed, instructions in a loop are red, and the start and end are marked.
+pub fn to_dot(program: &Program, analysis: &Analysis) -> String {
+    let len = program.instructions.len();
+    let looping: Vec<usize> = analysis.loops.iter().flatten().copied().collect();
+
+    let mut out = String::new();
+    out.push_str("digraph program {\n");
+    out.push_str("    node [shape=box, fontname=monospace];\n");
+    out.push_str("    start [shape=circle];\n");
+    out.push_str("    end [shape=doublecircle];\n");
+    out.push_str("    start -> i0;\n");
+    for (n, ins) in program.instructions.iter().enumerate() {
+        let mut style = Vec::new();
+        if !analysis.reachable[n] {
+            style.push("style=dashed, color=grey");
+        }
+        if looping.contains(&n) {
+            style.push("fontcolor=red, color=red");
+        }
+        let style = if style.is_empty() {
+            String::new()
+        } else {
+            format!(", {}", style.join(", "))
+        };
+        writeln!(out, "    i{} [label=\"{}: {}\"{}];", n, n, ins, style).unwrap();
+
+        let target = ins.successor(n);
+        if target == len as i64 {
+            writeln!(out, "    i{} -> end;", n).unwrap();
+        } else if target >= 0 && target < len as i64 {
+            writeln!(out, "    i{} -> i{};", n, target).unwrap();
+        } else {
+            writeln!(
+                out,
+                "    out{} [label=\"{}\", shape=plain, fontcolor=red];",
+                n, target
+            )
+            .unwrap();
+            writeln!(out, "    i{} -> out{} [color=red];", n, n).unwrap();
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+fn program(contents: &str) -> Program {
+    crate::asm::assemble(contents).unwrap()
+}
+
+#[test]
+fn test_example() {
+    let example =
+        program("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6");
+    let analysis = Analysis::new(&example);
+    assert_eq!(Exit::Loops(1), analysis.exit);
+    assert_eq!(vec![vec![1, 2, 6, 7, 3, 4]], analysis.loops);
+    assert_eq!(vec![5, 8], analysis.unreachable());
+    assert_eq!(
+        vec![
+            "infinite loop, 1 runs twice".to_string(),
+            "unreachable instructions [5, 8]".to_string()
+        ],
+        analysis.warnings()
+    );
+}
+
+#[test]
+fn test_clean_program() {
+    let analysis = Analysis::new(&program("acc +1\njmp +2\nacc +5\nnop +0"));
+    assert_eq!(Exit::Halts, analysis.exit);
+    assert_eq!(Vec::<Vec<usize>>::new(), analysis.loops);
+    assert_eq!(
+        vec!["unreachable instructions [2]".to_string()],
+        analysis.warnings()
+    );
+
+    let analysis = Analysis::new(&program("acc +1\nnop +0"));
+    assert!(analysis.warnings().is_empty());
+}
+
+#[test]
+fn test_hidden_loops() {
+    // The program halts, but there's a loop it never gets to, and a jmp out of the program.
+    let analysis = Analysis::new(&program("jmp +3\njmp +0\njmp -7\nacc +1"));
+    assert_eq!(Exit::Halts, analysis.exit);
+    assert_eq!(vec![vec![1]], analysis.loops);
+    assert_eq!(
+        vec![
+            "unreachable infinite loop through [1]".to_string(),
+            "unreachable instructions [1, 2]".to_string()
+        ],
+        analysis.warnings()
+    );
+
+    let analysis = Analysis::new(&program("acc +1\njmp -7"));
+    assert_eq!(Exit::Escapes(1), analysis.exit);
+}
+
+#[test]
+fn test_to_dot() {
+    let p = program("jmp +2\njmp -1\nacc +1\njmp -9");
+    let dot = to_dot(&p, &Analysis::new(&p));
+    let expected = "\
+digraph program {
+    node [shape=box, fontname=monospace];
+    start [shape=circle];
+    end [shape=doublecircle];
+    start -> i0;
+    i0 [label=\"0: jmp +2\"];
+    i0 -> i2;
+    i1 [label=\"1: jmp -1\", style=dashed, color=grey];
+    i1 -> i0;
+    i2 [label=\"2: acc +1\"];
+    i2 -> i3;
+    i3 [label=\"3: jmp -9\"];
+    out3 [label=\"-6\", shape=plain, fontcolor=red];
+    i3 -> out3 [color=red];
+}
+";
+    assert_eq!(expected, dot);
+}
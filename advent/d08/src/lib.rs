@@ -0,0 +1,205 @@
+use nom::character::complete::{digit1, space1};
+use nom::error::ErrorKind;
+use nom::{sequence::tuple, IResult};
+use nom::bytes::complete::{is_a, take};
+use common::ParseError;
+use vm::{Registers, Status, Vm};
+
+pub mod asm;
+pub mod cfg;
+pub mod repair;
+pub mod vm;
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub enum Instruction{
+    Nop(i32),
+    Jmp(i32),
+    Acc(i32),
+}
+
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+}
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct RanInstruction {
+    pub instruction: Instruction,
+    pub pointer: i32,
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct Computer {
+    pub program: Program,
+    pub accumulator: i32,
+    pub instruction_pointer: i32,
+}
+
+impl Computer { 
+
+    pub fn next(& mut self) -> Result<RanInstruction, String>{
+        let current_pointer = self.instruction_pointer;
+        let ins = match self.program.instructions.get(current_pointer as usize) {
+            Some(i) if current_pointer >= 0 => i,
+            _ => return Err(format!("Incorrect instruction {}", current_pointer)),
+        };
+        let mut registers = Registers{accumulator: self.accumulator, pointer: current_pointer};
+        ins.execute(&mut registers);
+
+        self.accumulator = registers.accumulator;
+        self.instruction_pointer = registers.pointer;
+        return Ok(RanInstruction {instruction: ins.clone(), pointer: current_pointer});
+    }
+
+    pub fn new(content: &str) -> Computer {
+        return Computer::parse(content).unwrap();
+    }
+
+    pub fn parse(content: &str) -> Result<Computer, ParseError> {
+        let program = parse_program(content)?;
+        return Ok(Computer{program, accumulator: 0, instruction_pointer: 0});
+    }
+
+    // run runs the program until an instruction is about to run a second time, and returns the
+    // accumulator at that point. If the program ends before that, there's nothing to return.
+    pub fn run(& mut self) -> Option<i32>{
+        let mut vm = Vm::new(self.program.clone());
+        vm.registers = Registers{accumulator: self.accumulator, pointer: self.instruction_pointer};
+        let status = vm.cont();
+
+        self.accumulator = vm.registers.accumulator;
+        self.instruction_pointer = vm.registers.pointer;
+        match status {
+            Status::Looped(_) => Some(self.accumulator),
+            _ => None,
+        }
+    }
+}
+
+// parse_program reads one instruction per line, and stops at the first line that isn't one.
+pub fn parse_program(contents: &str) -> Result<Program, ParseError>{
+    let mut prog = Vec::new();
+    for line in contents.lines() {
+        match parse_line(line) {
+            Ok(("", ins)) => prog.push(ins),
+            Ok((rest, _)) => return Err(ParseError::at(contents, rest, "Unexpected text after instruction")),
+            Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+                return Err(ParseError::at(contents, e.input, "Could not parse instruction"))
+            }
+            Err(nom::Err::Incomplete(_)) => return Err(ParseError::at(contents, line, "Could not parse instruction")),
+        }
+    }
+
+    if prog.is_empty() {
+        return Err(ParseError::at(contents, contents, "Program has no instructions"));
+    }
+    return Ok(Program{instructions: prog});
+}
+
+fn parse_line(i: &str) -> IResult<&str, Instruction> {
+    let mut line = tuple((
+        take(3usize),
+        space1,
+        is_a("+-"),
+        digit1
+    ));
+
+    let (s, (operation, _, operand, amount)) = line(i)?;
+
+    let operation_amount = match amount.parse() {
+        Ok(q) => q,
+        Err(_) => return Err(nom::Err::Failure(nom::error::make_error(amount, ErrorKind::Digit)))
+    };
+
+    let operation_amount = match operand {
+        "+" => operation_amount,
+        "-" => operation_amount * -1,
+        _ => return Err(nom::Err::Failure(nom::error::make_error(operand, ErrorKind::IsA)))
+    };
+
+    let op = match vm::opcode(operation) {
+        Some(op) => (op.build)(operation_amount),
+        None => return Err(nom::Err::Failure(nom::error::make_error(operation, ErrorKind::Tag)))
+    };
+
+    Ok((s, op))
+}
+
+// Day08 is the handheld game console's boot code.
+pub struct Day08;
+
+impl common::Solver for Day08 {
+    type Input<'a> = Computer;
+
+    fn parse(contents: &str) -> Result<Computer, ParseError> {
+        Computer::parse(contents)
+    }
+
+    // part1 is the accumulator right before any instruction runs a second time.
+    fn part1(computer: &Computer) -> Result<String, String> {
+        match computer.clone().run() {
+            Some(accum) => Ok(accum.to_string()),
+            None => Err("The program terminated without looping".to_string()),
+        }
+    }
+
+    // part2 finds the one jmp or nop to swap for the other that makes the program terminate, and
+    // returns the accumulator at that point.
+    fn part2(computer: &Computer) -> Result<String, String> {
+        match repair::find_repair(&computer.program) {
+            Some(repair) => Ok(repair.accumulator.to_string()),
+            None => Err("No single swap makes the program terminate".to_string()),
+        }
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day08>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day08>(contents)
+}
+
+#[test]
+fn test_program(){
+    let contents = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+";
+
+    let mut computer = Computer::new(contents);
+    let accum = computer.run();
+    assert_eq!(Some(5), accum);
+    assert_eq!(Ok("8".to_string()), part2(contents));
+
+}
+
+
+
+#[test]
+fn test_bad_program() {
+    assert_eq!(
+        Err(ParseError::new(2, 1, "mul", "Could not parse instruction")),
+        Computer::parse("nop +0\nmul +1\n")
+    );
+    assert_eq!(
+        Err(ParseError::new(1, 5, "1", "Could not parse instruction")),
+        Computer::parse("acc 1")
+    );
+    assert_eq!(
+        Err(ParseError::new(1, 7, " # loop", "Unexpected text after instruction")),
+        Computer::parse("jmp +0 # loop")
+    );
+    assert_eq!(
+        Err(ParseError::new(1, 5, "+-", "Could not parse instruction")),
+        Computer::parse("acc +-1")
+    );
+}
@@ -1,186 +1,83 @@
 use clap::{App, Arg};
-use nom::character::complete::{digit1, space1};
-use nom::error::ErrorKind;
-use nom::lib::std::collections::HashMap;
-use nom::{sequence::tuple, IResult};
+use d08::asm;
+use d08::cfg::{self, Analysis};
+use d08::vm::{self, Vm};
 use std::fs;
-use nom::sequence::terminated;
-use nom::multi::many1;
-use crate::Instruction::{Nop, Jmp, Acc};
-use nom::bytes::complete::{is_a, take, tag};
-
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
-enum Instruction{
-    Nop(i32),
-    Jmp(i32),
-    Acc(i32),
-}
-
-impl Instruction {
-}
-
-
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
-struct Program {
-    instructions: Vec<Instruction>,
-}
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
-struct RanInstruction {
-    instruction: Instruction,
-    pointer: i32,
-}
-
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
-struct Computer {
-    program: Program,
-    accumulator: i32,
-    instruction_pointer: i32,
-}
-
-impl Computer { 
-
-    fn next(& mut self) -> Result<RanInstruction, String>{
-        let current_pointer = self.instruction_pointer;
-        let ins = match self.program.instructions.get(current_pointer as usize) {
-            Some(i) => i,
-            None => return Err(format!("Incorrect instruction {}", current_pointer)),
-        };
-        let future_point = match ins {
-            Nop(_) => current_pointer+1,
-            Jmp(i) => current_pointer+i,
-            Acc(i) => {
-                self.accumulator += i;
-                current_pointer+1
-            },
-        };
-
-        self.instruction_pointer = future_point;
-        return Ok(RanInstruction {instruction: ins.clone(), pointer: current_pointer});
-    }
-
-    fn new(content: &str) -> Computer {
-        let program = parse_program(content).unwrap();
-        return Computer{program, accumulator: 0, instruction_pointer: 0};
-    }
-
-    fn run(& mut self) -> Option<i32>{
-        let mut m = HashMap::new();
-        loop {
-            if m.contains_key(&self.instruction_pointer) {
-                return Some(self.accumulator);
-            }
-
-            let ran_instruction = match self.next() {
-                Ok(ins) => ins,
-                Err(_) => return None,
-            };
-            m.insert(ran_instruction.pointer, true);
-        }
-    }
-}
-
-fn parse_program(contents: &str) -> Result<Program, nom::Err<nom::error::Error<&str>>>{
-    let mut full_program = many1(terminated(parse_line, tag("\n")));
-
-    if let Err(h) = full_program(contents) {
-        return Err(h);
-    }
-
-    let (_, prog) = full_program(contents)?;
-    return Ok(Program{instructions: prog});
-}
-
-fn parse_line(i: &str) -> IResult<&str, Instruction> {
-    let mut line = tuple((
-        take(3usize),
-        space1,
-        is_a("+-"),
-        digit1
-    ));
-
-    let (s, (operation, _, operand, amount)) = line(i)?;
-
-    let operation_amount = match amount.parse() {
-        Ok(q) => q,
-        Err(_) => return Err(nom::Err::Failure(nom::error::make_error(s, ErrorKind::Digit)))
-    };
-
-    let operation_amount = match operand {
-        "+" => operation_amount,
-        "-" => operation_amount * -1,
-        _ => { panic!("Impossible operand")}
-    };
-
-    let op = match operation {
-        "nop" => Nop(operation_amount),
-        "jmp" => Jmp(operation_amount),
-        "acc" => Acc(operation_amount),
-        _ => return Err(nom::Err::Failure(nom::error::make_error(s, ErrorKind::TakeWhile1)))
-    };
-
-    Ok((s, op))
-}
-
-#[test]
-fn test_program(){
-    let contents = "\
-nop +0
-acc +1
-jmp +4
-acc +3
-jmp -3
-acc -99
-acc +1
-jmp -4
-acc +6
-";
-
-    let mut computer = Computer::new(contents);
-    let accum = computer.run();
-    assert_eq!(5, accum)
-
-}
-
-
+use std::io;
+use std::process;
+
+// d08 works with handheld console programs, written in the puzzle's format or as assembly with
+// labels and comments:
+//   debug         step through the program, reading debugger commands from stdin (the default)
+//   disassemble   print the program with its jump targets labelled
+//   check         warn about infinite loops and unreachable code without running anything
+//   dot           print the control flow graph for graphviz
 fn main() {
+    let input = || {
+        Arg::new("INPUT")
+            .help("Sets the input file to use")
+            .required(true)
+            .index(1)
+    };
     let matches = App::new("Reader for advent")
         .version("1.0")
         .author("Sean Tyler Myers <seanmyers0608@gmail.com>")
-        .about("Reads advent calendar programs")
-        .arg(
-            Arg::new("INPUT")
-                .about("Sets the input file to use")
-                .required(true)
-                .index(1),
+        .about("Works with handheld console programs")
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
+        .arg(input())
+        .subcommand(
+            App::new("debug")
+                .about("Steps through the program")
+                .arg(input()),
+        )
+        .subcommand(
+            App::new("disassemble")
+                .about("Prints the program with its jump targets labelled")
+                .arg(input()),
+        )
+        .subcommand(
+            App::new("check")
+                .about("Warns about infinite loops and unreachable code")
+                .arg(input()),
+        )
+        .subcommand(
+            App::new("dot")
+                .about("Prints the control flow graph for graphviz")
+                .arg(input()),
         )
         .get_matches();
-
-    let txt_location: &str = matches.value_of("INPUT").unwrap();
-    let contents = fs::read_to_string(txt_location).unwrap();
-    let mut computer = Computer::new(&contents);
-    let accum = computer.run();
-    println!("{}", accum.unwrap());
-
-    let computer = Computer::new(&contents);
-
-    for (loc, ins) in computer.program.instructions.iter().enumerate() {
-        let swapped_ins = match ins {
-            Nop(i) => Some(Instruction::Jmp(i.clone())),
-            Jmp(i) => Some(Instruction::Nop(i.clone())),
-            Acc(_) => None,
-        };
-
-        if let Some(x) = swapped_ins {
-            let mut cloned = computer.clone();
-            cloned.program.instructions[loc] = x;
-            match cloned.run() {
-                Some(_) => continue,
-                None => {
-                    println!("The correct mutation has been found, accumulator at {}", cloned.accumulator)
-                }
+    let (command, matches) = matches.subcommand().unwrap_or(("debug", &matches));
+    let filename = matches.value_of("INPUT").unwrap();
+
+    let contents = fs::read_to_string(filename).unwrap_or_else(|err| {
+        eprintln!("Problem reading {}: {}", filename, err);
+        process::exit(1);
+    });
+    let program = asm::assemble(&contents).unwrap_or_else(|err| {
+        eprintln!("Problem parsing {}", err.in_file(filename));
+        process::exit(1);
+    });
+
+    match command {
+        "debug" => {
+            let mut vm = Vm::new(program);
+            let stdin = io::stdin();
+            if let Err(err) = vm::debug(&mut vm, stdin.lock(), io::stdout()) {
+                eprintln!("Problem debugging {}: {}", filename, err);
+                process::exit(1);
+            }
+        }
+        "disassemble" => print!("{}", asm::disassemble(&program)),
+        "check" => {
+            let warnings = Analysis::new(&program).warnings();
+            for warning in warnings.iter() {
+                println!("{}: {}", filename, warning);
+            }
+            if !warnings.is_empty() {
+                process::exit(1);
             }
-        } else {
-            continue;
         }
+        _ => print!("{}", cfg::to_dot(&program, &Analysis::new(&program))),
     }
 }
@@ -0,0 +1,174 @@
+use crate::vm::{Status, Vm};
+use crate::{Instruction, Program};
+use std::collections::VecDeque;
+
+// Repair is the one instruction to swap to make a program terminate, and what the accumulator
+// ends up as once it does.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Repair {
+    pub pointer: usize,
+    pub from: Instruction,
+    pub to: Instruction,
+    pub accumulator: i32,
+}
+
+// flipped is ins with jmp and nop swapped, or None for anything else.
+pub fn flipped(ins: &Instruction) -> Option<Instruction> {
+    match ins {
+        Instruction::Jmp(i) => Some(Instruction::Nop(*i)),
+        Instruction::Nop(i) => Some(Instruction::Jmp(*i)),
+        Instruction::Acc(_) => None,
+    }
+}
+
+// reaches_end says, for every instruction, whether running the program from there ends up just
+// past the last instruction. It walks the instruction graph backwards from the end, so every
+// instruction is looked at once.
+pub fn reaches_end(program: &Program) -> Vec<bool> {
+    let len = program.instructions.len();
+    // predecessors[n] is every instruction that goes to n, with n == len being the end.
+    let mut predecessors = vec![Vec::new(); len + 1];
+    for (pointer, ins) in program.instructions.iter().enumerate() {
+        let next = ins.successor(pointer);
+        if next >= 0 && next <= len as i64 {
+            predecessors[next as usize].push(pointer);
+        }
+    }
+
+    let mut reaches = vec![false; len + 1];
+    reaches[len] = true;
+    let mut queue = VecDeque::new();
+    queue.push_back(len);
+    while let Some(n) = queue.pop_front() {
+        for &p in predecessors[n].iter() {
+            if !reaches[p] {
+                reaches[p] = true;
+                queue.push_back(p);
+            }
+        }
+    }
+
+    reaches.truncate(len);
+    reaches
+}
+
+// find_repair finds the jmp or nop that, swapped for the other, makes program terminate. Only
+// instructions the program actually runs before looping can matter, and swapping one of them fixes
+// the program exactly when its new successor is the end or reaches the end. None means the
+// program already terminates, or no single swap fixes it.
+pub fn find_repair(program: &Program) -> Option<Repair> {
+    let len = program.instructions.len();
+    let reaches = reaches_end(program);
+
+    let mut visited = vec![false; len];
+    let mut pointer = 0;
+    let mut candidate = None;
+    while pointer < len && !visited[pointer] {
+        visited[pointer] = true;
+        let ins = &program.instructions[pointer];
+        if let Some(to) = flipped(ins) {
+            let next = to.successor(pointer);
+            if next == len as i64 || (next >= 0 && next < len as i64 && reaches[next as usize]) {
+                candidate = Some((pointer, ins.clone(), to));
+                break;
+            }
+        }
+
+        let next = ins.successor(pointer);
+        if next < 0 || next > len as i64 {
+            return None;
+        }
+        pointer = next as usize;
+    }
+    if pointer == len {
+        return None;
+    }
+
+    let (pointer, from, to) = candidate?;
+    let mut repaired = program.clone();
+    repaired.instructions[pointer] = to.clone();
+    let mut vm = Vm::new(repaired);
+    match vm.cont() {
+        Status::Halted => Some(Repair {
+            pointer,
+            from,
+            to,
+            accumulator: vm.registers.accumulator,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+fn program(contents: &str) -> Program {
+    crate::parse_program(contents).unwrap()
+}
+
+#[test]
+fn test_reaches_end() {
+    let example = program(
+        "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+",
+    );
+    assert_eq!(
+        vec![false, false, false, false, false, false, false, false, true],
+        reaches_end(&example)
+    );
+}
+
+#[test]
+fn test_find_repair() {
+    let example = program(
+        "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+",
+    );
+    assert_eq!(
+        Some(Repair {
+            pointer: 7,
+            from: Instruction::Jmp(-4),
+            to: Instruction::Nop(-4),
+            accumulator: 8,
+        }),
+        find_repair(&example)
+    );
+}
+
+#[test]
+fn test_repair_nop() {
+    // The nop on 0 has to become a jmp to skip past the loop.
+    let skip = program("nop +3\nacc +1\njmp -1\nacc +2");
+    assert_eq!(
+        Some(Repair {
+            pointer: 0,
+            from: Instruction::Nop(3),
+            to: Instruction::Jmp(3),
+            accumulator: 2,
+        }),
+        find_repair(&skip)
+    );
+}
+
+#[test]
+fn test_nothing_to_repair() {
+    assert_eq!(None, find_repair(&program("acc +1\nnop +0")));
+    // Swapping either jmp still loops.
+    assert_eq!(None, find_repair(&program("jmp +0\njmp -1")));
+}
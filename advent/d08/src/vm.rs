@@ -0,0 +1,500 @@
+use crate::{Instruction, Program};
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+// Only this many of the most recent steps are kept in the trace.
+const TRACE_LIMIT: usize = 10_000;
+
+// Opcode is one instruction the console understands: what it's written as, what it does, and how
+// to build it from its argument.
+pub struct Opcode {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub build: fn(i32) -> Instruction,
+}
+
+// INSTRUCTION_SET is every instruction the console understands. Each one takes a signed argument.
+pub const INSTRUCTION_SET: [Opcode; 3] = [
+    Opcode {
+        name: "acc",
+        summary: "add the argument to the accumulator, then go to the next instruction",
+        build: Instruction::Acc,
+    },
+    Opcode {
+        name: "jmp",
+        summary: "jump by the argument, relative to this instruction",
+        build: Instruction::Jmp,
+    },
+    Opcode {
+        name: "nop",
+        summary: "do nothing, then go to the next instruction",
+        build: Instruction::Nop,
+    },
+];
+
+// opcode looks an instruction up by name.
+pub fn opcode(name: &str) -> Option<&'static Opcode> {
+    INSTRUCTION_SET.iter().find(|op| op.name == name)
+}
+
+impl Instruction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Instruction::Acc(_) => "acc",
+            Instruction::Jmp(_) => "jmp",
+            Instruction::Nop(_) => "nop",
+        }
+    }
+
+    pub fn argument(&self) -> i32 {
+        match self {
+            Instruction::Acc(i) | Instruction::Jmp(i) | Instruction::Nop(i) => *i,
+        }
+    }
+
+    // successor is where the pointer goes after running the instruction at pointer. It can be
+    // outside the program.
+    pub fn successor(&self, pointer: usize) -> i64 {
+        match self {
+            Instruction::Jmp(i) => pointer as i64 + i64::from(*i),
+            _ => pointer as i64 + 1,
+        }
+    }
+
+    // execute runs the instruction against registers, leaving the pointer on the next one to run.
+    pub fn execute(&self, registers: &mut Registers) {
+        match self {
+            Instruction::Acc(i) => {
+                registers.accumulator += i;
+                registers.pointer += 1;
+            }
+            Instruction::Jmp(i) => registers.pointer += i,
+            Instruction::Nop(_) => registers.pointer += 1,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:+}", self.name(), self.argument())
+    }
+}
+
+// Registers is everything the console keeps track of while it runs.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Registers {
+    pub accumulator: i32,
+    pub pointer: i32,
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "acc={} ip={}", self.accumulator, self.pointer)
+    }
+}
+
+// Status is where the VM got to after running.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Status {
+    // There's more to run.
+    Running,
+    // The pointer is just past the last instruction, which is how programs are meant to end.
+    Halted,
+    // The pointer is somewhere else outside the program.
+    Crashed(i32),
+    // The pointer is on an instruction that has already run, so the program will go round forever.
+    Looped(usize),
+    // The pointer is on an instruction with a breakpoint.
+    Breakpoint(usize),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Running => write!(f, "running"),
+            Status::Halted => write!(f, "halted"),
+            Status::Crashed(p) => write!(f, "crashed, jumped to {}", p),
+            Status::Looped(p) => write!(f, "looped, {} has already run", p),
+            Status::Breakpoint(p) => write!(f, "stopped at breakpoint {}", p),
+        }
+    }
+}
+
+// Step is one instruction the VM ran, and the registers it left behind.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Step {
+    pub number: u64,
+    pub pointer: usize,
+    pub instruction: Instruction,
+    pub registers: Registers,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} {:>4}: {:<8} -> {}",
+            self.number,
+            self.pointer,
+            self.instruction.to_string(),
+            self.registers
+        )
+    }
+}
+
+// Vm runs a program one instruction at a time, remembering which instructions have run so it can
+// stop before going round a loop a second time.
+#[derive(Debug, Clone)]
+pub struct Vm {
+    program: Program,
+    pub registers: Registers,
+    pub breakpoints: BTreeSet<usize>,
+    visited: Vec<bool>,
+    trace: VecDeque<Step>,
+    steps: u64,
+}
+
+impl Vm {
+    pub fn new(program: Program) -> Vm {
+        let visited = vec![false; program.instructions.len()];
+        Vm {
+            program,
+            registers: Registers::default(),
+            breakpoints: BTreeSet::new(),
+            visited,
+            trace: VecDeque::new(),
+            steps: 0,
+        }
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    // reset puts the VM back to before anything ran, keeping its breakpoints.
+    pub fn reset(&mut self) {
+        self.registers = Registers::default();
+        self.visited = vec![false; self.program.instructions.len()];
+        self.trace.clear();
+        self.steps = 0;
+    }
+
+    // status is what would happen if the VM ran the next instruction.
+    pub fn status(&self) -> Status {
+        let pointer = self.registers.pointer;
+        let len = self.program.instructions.len();
+        if pointer == len as i32 {
+            Status::Halted
+        } else if pointer < 0 || pointer as usize > len {
+            Status::Crashed(pointer)
+        } else if self.visited[pointer as usize] {
+            Status::Looped(pointer as usize)
+        } else {
+            Status::Running
+        }
+    }
+
+    // step runs the next instruction, even one that has run before, unless the program is over.
+    pub fn step(&mut self) -> Status {
+        let pointer = match self.status() {
+            Status::Halted => return Status::Halted,
+            Status::Crashed(p) => return Status::Crashed(p),
+            _ => self.registers.pointer as usize,
+        };
+
+        let instruction = self.program.instructions[pointer].clone();
+        instruction.execute(&mut self.registers);
+        self.visited[pointer] = true;
+        self.steps += 1;
+
+        if self.trace.len() == TRACE_LIMIT {
+            self.trace.pop_front();
+        }
+        self.trace.push_back(Step {
+            number: self.steps,
+            pointer,
+            instruction,
+            registers: self.registers,
+        });
+
+        self.status()
+    }
+
+    // cont runs until the program ends, loops, or reaches a breakpoint. It always runs at least
+    // one instruction, so continuing from a breakpoint doesn't stop on the same one again.
+    pub fn cont(&mut self) -> Status {
+        loop {
+            let status = self.step();
+            if status != Status::Running {
+                return status;
+            }
+            let pointer = self.registers.pointer as usize;
+            if self.breakpoints.contains(&pointer) {
+                return Status::Breakpoint(pointer);
+            }
+        }
+    }
+
+    // trace is the most recent steps, oldest first.
+    pub fn trace(&self) -> impl Iterator<Item = &Step> {
+        self.trace.iter()
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    // dump describes the registers, and where the VM has got to.
+    pub fn dump(&self) -> String {
+        let next = match self.program.instructions.get(self.registers.pointer as usize) {
+            Some(ins) if self.registers.pointer >= 0 => ins.to_string(),
+            _ => "-".to_string(),
+        };
+        format!(
+            "{} steps={} next={} status={}",
+            self.registers,
+            self.steps,
+            next,
+            self.status()
+        )
+    }
+
+    // listing prints the instructions within around of the pointer, marking the pointer with >
+    // and breakpoints with *.
+    pub fn listing(&self, around: usize) -> String {
+        let pointer = self.registers.pointer.max(0) as usize;
+        let start = pointer.saturating_sub(around);
+        let end = (pointer + around + 1).min(self.program.instructions.len());
+
+        let mut out = String::new();
+        for i in start..end {
+            let here = if i == pointer { '>' } else { ' ' };
+            let breakpoint = if self.breakpoints.contains(&i) { '*' } else { ' ' };
+            out.push_str(&format!(
+                "{}{}{:>4}: {}\n",
+                here, breakpoint, i, self.program.instructions[i]
+            ));
+        }
+        out
+    }
+}
+
+const HELP: &str = "\
+step [n]      run the next n instructions (s)
+continue      run until the program ends, loops or hits a breakpoint (c)
+break <n>     stop before running instruction n (b)
+delete <n>    remove the breakpoint on instruction n (d)
+registers     show the registers (r)
+list [n]      show the instructions within n of the pointer (l)
+trace [n]     show the last n instructions that ran (t)
+reset         start the program again
+help          show this (h)
+quit          stop debugging (q)
+";
+
+// debug is an interactive debugger for vm, reading commands from input until it runs out or is
+// told to quit. Everything it has to say goes to output.
+pub fn debug<R: BufRead, W: Write>(vm: &mut Vm, input: R, mut output: W) -> io::Result<()> {
+    writeln!(
+        output,
+        "{} instructions loaded, type help for commands",
+        vm.program.instructions.len()
+    )?;
+    write!(output, "{}", vm.listing(2))?;
+
+    let mut lines = input.lines();
+    loop {
+        write!(output, "(vm) ")?;
+        output.flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, arg) = match words.as_slice() {
+            [] => continue,
+            [command] => (*command, None),
+            [command, arg] => (*command, Some(*arg)),
+            _ => {
+                writeln!(output, "Commands take at most one argument")?;
+                continue;
+            }
+        };
+        let number = match arg.map(|a| a.parse::<usize>()) {
+            Some(Ok(n)) => Some(n),
+            Some(Err(_)) => {
+                writeln!(output, "{} is not a number", arg.unwrap())?;
+                continue;
+            }
+            None => None,
+        };
+
+        match command {
+            "s" | "step" => {
+                let mut status = vm.status();
+                for _ in 0..number.unwrap_or(1) {
+                    status = vm.step();
+                    if let Some(step) = vm.trace.back() {
+                        writeln!(output, "{}", step)?;
+                    }
+                    if status == Status::Halted || matches!(status, Status::Crashed(_)) {
+                        break;
+                    }
+                }
+                writeln!(output, "{}", status)?;
+            }
+            "c" | "continue" => {
+                let status = vm.cont();
+                writeln!(output, "{}", status)?;
+                writeln!(output, "{}", vm.dump())?;
+            }
+            "b" | "break" | "d" | "delete" => {
+                let n = match number {
+                    Some(n) if n < vm.program.instructions.len() => n,
+                    Some(n) => {
+                        writeln!(output, "There is no instruction {}", n)?;
+                        continue;
+                    }
+                    None => {
+                        writeln!(output, "{} needs an instruction number", command)?;
+                        continue;
+                    }
+                };
+                if command.starts_with('b') {
+                    vm.breakpoints.insert(n);
+                    writeln!(output, "Breakpoint on {}: {}", n, vm.program.instructions[n])?;
+                } else if vm.breakpoints.remove(&n) {
+                    writeln!(output, "Removed breakpoint on {}", n)?;
+                } else {
+                    writeln!(output, "No breakpoint on {}", n)?;
+                }
+            }
+            "r" | "registers" => writeln!(output, "{}", vm.dump())?,
+            "l" | "list" => write!(output, "{}", vm.listing(number.unwrap_or(5)))?,
+            "t" | "trace" => {
+                let n = number.unwrap_or(10);
+                for step in vm.trace.iter().skip(vm.trace.len().saturating_sub(n)) {
+                    writeln!(output, "{}", step)?;
+                }
+            }
+            "reset" => {
+                vm.reset();
+                writeln!(output, "{}", vm.dump())?;
+            }
+            "h" | "help" => {
+                write!(output, "{}", HELP)?;
+                writeln!(output, "\nThe console understands:")?;
+                for op in INSTRUCTION_SET.iter() {
+                    writeln!(output, "{} <n>       {}", op.name, op.summary)?;
+                }
+            }
+            "q" | "quit" => return Ok(()),
+            _ => writeln!(output, "Unknown command {}, type help for commands", command)?,
+        }
+    }
+}
+
+#[cfg(test)]
+fn example() -> Vm {
+    let contents = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+";
+    Vm::new(crate::parse_program(contents).unwrap())
+}
+
+#[test]
+fn test_instruction_set() {
+    for op in INSTRUCTION_SET.iter() {
+        let ins = (op.build)(-3);
+        assert_eq!(op.name, ins.name());
+        assert_eq!(-3, ins.argument());
+        assert_eq!(format!("{} -3", op.name), ins.to_string());
+    }
+    assert!(opcode("mul").is_none());
+}
+
+#[test]
+fn test_step_until_loop() {
+    let mut vm = example();
+    assert_eq!(Status::Running, vm.step());
+    assert_eq!(Status::Running, vm.step());
+    assert_eq!(
+        Registers {
+            accumulator: 1,
+            pointer: 2
+        },
+        vm.registers
+    );
+
+    assert_eq!(Status::Looped(1), vm.cont());
+    assert_eq!(5, vm.registers.accumulator);
+    assert_eq!(7, vm.steps());
+    let pointers: Vec<usize> = vm.trace().map(|s| s.pointer).collect();
+    assert_eq!(vec![0, 1, 2, 6, 7, 3, 4], pointers);
+}
+
+#[test]
+fn test_breakpoints() {
+    let mut vm = example();
+    vm.breakpoints.insert(3);
+    assert_eq!(Status::Breakpoint(3), vm.cont());
+    assert_eq!(2, vm.registers.accumulator);
+
+    // Continuing runs the instruction under the breakpoint rather than stopping on it again.
+    assert_eq!(Status::Looped(1), vm.cont());
+    assert_eq!(5, vm.registers.accumulator);
+
+    vm.reset();
+    assert_eq!(0, vm.steps());
+    assert_eq!(Status::Breakpoint(3), vm.cont());
+}
+
+#[test]
+fn test_halts_and_crashes() {
+    let mut vm = example();
+    vm.program.instructions[7] = Instruction::Nop(-4);
+    assert_eq!(Status::Halted, vm.cont());
+    assert_eq!(8, vm.registers.accumulator);
+    assert_eq!(Status::Halted, vm.step());
+
+    let mut vm = Vm::new(crate::parse_program("jmp -1").unwrap());
+    assert_eq!(Status::Crashed(-1), vm.cont());
+    assert_eq!("acc=0 ip=-1 steps=1 next=- status=crashed, jumped to -1", vm.dump());
+}
+
+#[test]
+fn test_debugger() {
+    let mut vm = example();
+    let commands = "break 4\nc\nstep 2\nr\ntrace 2\nbreak 99\nbogus\nq\nstep\n";
+    let mut output = Vec::new();
+    debug(&mut vm, commands.as_bytes(), &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let expected = "\
+9 instructions loaded, type help for commands
+>    0: nop +0
+     1: acc +1
+     2: jmp +4
+(vm) Breakpoint on 4: jmp -3
+(vm) stopped at breakpoint 4
+acc=5 ip=4 steps=6 next=jmp -3 status=running
+";
+    assert!(output.starts_with(expected), "{}", output);
+    assert!(output.contains("#7    4: jmp -3   -> acc=5 ip=1\n#8    1: acc +1   -> acc=6 ip=2\nlooped, 2 has already run\n"));
+    assert!(output.contains("(vm) acc=6 ip=2 steps=8 next=jmp +4 status=looped, 2 has already run\n"));
+    assert!(output.contains("(vm) There is no instruction 99\n"));
+    assert!(output.contains("(vm) Unknown command bogus, type help for commands\n"));
+    // Nothing after quit runs.
+    assert!(output.ends_with("(vm) "));
+    assert_eq!(8, vm.steps());
+}
@@ -0,0 +1,39 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use d09::stream;
+use d09::MovingCipher;
+
+// cipher makes up count numbers with a preamble of 25, where every number is the sum of the
+// first two in the window before it, until the last one which can't be.
+fn cipher(count: usize) -> String {
+    let mut numbers: Vec<i64> = (1..=25).collect();
+    while numbers.len() < count - 1 {
+        let window = &numbers[numbers.len() - 25..];
+        numbers.push(window[0] + window[1]);
+    }
+    numbers.push(1);
+
+    let lines: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+    lines.join("\n")
+}
+
+fn bench_find_cracks(c: &mut Criterion) {
+    let contents = cipher(1000);
+    let cipher = MovingCipher::new(25, &contents).unwrap();
+    c.bench_function("MovingCipher::find_cracks 1000 numbers", |b| {
+        b.iter_batched(
+            || cipher.clone(),
+            |mut cipher| cipher.find_cracks(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_scan(c: &mut Criterion) {
+    let contents = cipher(1000);
+    c.bench_function("stream::scan 1000 numbers", |b| {
+        b.iter(|| stream::scan(contents.as_bytes(), 25))
+    });
+}
+
+criterion_group!(benches, bench_find_cracks, bench_scan);
+criterion_main!(benches);
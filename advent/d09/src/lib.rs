@@ -0,0 +1,363 @@
+use common::ParseError;
+use std::ops::Range;
+
+pub mod stream;
+
+// FullCipher is all the numbers in the cipher to be "understood", but doesn't do the core
+// algorithm which traverses through these numbers.
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct FullCipher {
+    pub numbers: Vec<i64>
+}
+
+impl FullCipher {
+    pub fn new(content: &str) -> Result<FullCipher, ParseError> {
+        let mut numbers: Vec<i64> = Vec::new();
+        for line in content.lines() {
+            let num: i64 = match line.parse() {
+                Ok(i) => i,
+                Err(_) => return Err(ParseError::at(content, line, "Could not parse number"))
+            };
+
+            numbers.push(num)
+        }
+
+        return Ok(FullCipher{numbers});
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MovingCipher {
+    full_cipher: FullCipher,
+
+    // These define the "sliding window" into the full cipher.
+    start_position: usize,
+    end_position: usize,
+
+    // window_size is the amount the sliding window can be. In the program, it's referred to as
+    // the "preamble", but the preamble slides along with the cipher, which makes it a window :)
+    window_size: u32,
+}
+
+impl MovingCipher {
+    pub fn new(window: u32, content: &str) -> Result<MovingCipher, String> {
+        return MovingCipher::from_cipher(window, FullCipher::new(content)?);
+    }
+
+    pub fn from_cipher(window: u32, full_cipher: FullCipher) -> Result<MovingCipher, String> {
+        if window > full_cipher.numbers.len() as u32 {
+            return Err("Don't support windows that large".to_string());
+        }
+
+        return Ok(MovingCipher{full_cipher, window_size: window, start_position: 0, end_position: window as usize});
+    }
+
+    pub fn find_cracks(& mut self) -> Result<i64, String> {
+
+        loop {
+            let next_pos = self.end_position;
+            let next_num: i64 = match self.full_cipher.numbers.get(next_pos) {
+                Some(i) => { i.clone() }
+                None => {return Err("Reached end of the line".to_string())}
+            };
+
+            let legit = self.within_pool(next_num)?;
+
+            if legit {
+                self.move_window();
+            } else {
+                return Ok(next_num)
+            }
+        }
+
+    }
+
+    fn within_pool(&self, num: i64) -> Result<bool, String> {
+        let current_pool = match self.calculate_pool(){
+            Some(p) => p,
+            None => return Err("Pool doesn't exist".to_string()),
+        };
+
+        for i in current_pool.iter() {
+            if num == i.clone() {
+                return Ok(true);
+            }
+        }
+
+        return Ok(false);
+
+    }
+
+    fn move_window(& mut self) {
+        self.start_position += 1;
+        self.end_position += 1;
+    }
+
+    fn calculate_pool(&self) -> Option<Vec<i64>> {
+        let mut pool = Vec::new();
+
+        for i in self.start_position..self.end_position {
+            for j in i+1..self.end_position {
+                if i == j {
+                    continue;
+                }
+
+                let x = self.full_cipher.numbers.get(i)?;
+                let y = self.full_cipher.numbers.get(j)?;
+                pool.push((x+y) as i64);
+            }
+        }
+
+        return Some(pool);
+    }
+
+    fn see_window(&self) -> Vec<i64> {
+        let mut v = Vec::new();
+        for i in self.start_position..self.end_position {
+            let x = self.full_cipher.numbers.get(i).unwrap();
+            v.push(x.clone());
+        }
+        return v;
+    }
+}
+
+#[test]
+fn test_rolling(){
+    let content = "\
+35
+20
+15
+";
+    let cipher = MovingCipher::new(3, content).unwrap();
+    assert_eq!(vec![55, 50, 35], cipher.calculate_pool().unwrap())
+
+}
+
+#[test]
+fn test_rolling_window(){
+    let content = "\
+35
+20
+15
+25
+47
+40
+62
+55
+65
+95
+102
+117
+150
+182
+127
+219
+299
+277
+309
+576
+";
+
+    let mut cipher = MovingCipher::new(5, content).unwrap();
+    println!("{}", cipher.find_cracks().unwrap());
+
+}
+
+
+pub struct GrowingCipher {
+    full_cipher: FullCipher,
+    pub size: usize,
+    pub start: usize,
+}
+
+impl GrowingCipher {
+    pub fn new(start: usize, full_cipher: FullCipher) -> Result<GrowingCipher, String> {
+        if start >= full_cipher.numbers.len() {
+            return Err("Don't support starting positions that large".to_string());
+        }
+
+        return Ok(GrowingCipher{full_cipher, size: 2, start});
+    }
+
+    pub fn is_contiguous(&mut self, goal: i64) -> bool {
+        loop {
+            let mut accumulator = 0;
+            
+            for i in self.start..self.start+self.size{
+                accumulator = match self.full_cipher.numbers.get(i){
+                    Some(x) => x + accumulator,
+                    None => return false,
+                }
+            }
+
+            if accumulator == goal {
+                return true;
+            }
+
+            if accumulator > goal {
+                return false;
+            }
+
+            self.size += 1;
+        }
+    }
+}
+
+#[test]
+fn test_growing_cipher(){
+    let content = "\
+35
+20
+15
+25
+47
+40
+62
+55
+65
+95
+102
+117
+150
+182
+127
+219
+299
+277
+309
+576
+";
+    let full_cipher = FullCipher::new(content).unwrap();
+    
+    for i in 0..full_cipher.numbers.len(){
+        let mut cipher = GrowingCipher::new(i, full_cipher.clone()).unwrap();
+        if cipher.is_contiguous(127) {
+            println!("WE GOTTA {}, {}", cipher.start, cipher.size);
+            let mut smallest = full_cipher.numbers[cipher.start];
+            let mut largest = full_cipher.numbers[cipher.start];
+
+
+            for index in cipher.start..cipher.start+cipher.size {
+                if full_cipher.numbers[index] > largest {
+                    largest = full_cipher.numbers[index]
+                }
+
+                if full_cipher.numbers[index] < smallest {
+                    smallest = full_cipher.numbers[index]
+                }
+            }
+
+            println!("With my numbers combined: {}, {}, {}", smallest, largest, smallest+largest);
+            break;
+        }
+    }
+    
+
+}
+
+// weakness_range finds a contiguous run of at least two numbers adding up to goal, in one pass.
+// It keeps a window [start, end) and its sum: the end moves on while the sum is too small, and the
+// start catches up while it's too big. That only works because the numbers are never negative, so
+// growing the window never makes the sum smaller; the puzzle's numbers never are.
+pub fn weakness_range(numbers: &[i64], goal: i64) -> Option<Range<usize>> {
+    let mut start = 0;
+    let mut sum = 0;
+    for end in 0..numbers.len() {
+        sum += numbers[end];
+        while sum > goal && start < end {
+            sum -= numbers[start];
+            start += 1;
+        }
+        if sum == goal && end > start {
+            return Some(start..end + 1);
+        }
+    }
+
+    None
+}
+
+// find_weakness looks for a contiguous run of at least two numbers adding up to goal, and returns
+// the smallest and largest numbers of that run added together.
+pub fn find_weakness(full_cipher: &FullCipher, goal: i64) -> Option<i64> {
+    let strand = &full_cipher.numbers[weakness_range(&full_cipher.numbers, goal)?];
+    Some(strand.iter().min()? + strand.iter().max()?)
+}
+
+// The puzzle's preamble is 25 numbers long.
+const PREAMBLE: u32 = 25;
+
+// Day09 is the XMAS cipher.
+pub struct Day09;
+
+impl common::Solver for Day09 {
+    type Input<'a> = FullCipher;
+
+    fn parse(contents: &str) -> Result<FullCipher, ParseError> {
+        FullCipher::new(contents)
+    }
+
+    fn part1(full_cipher: &FullCipher) -> Result<String, String> {
+        let mut cipher = MovingCipher::from_cipher(PREAMBLE, full_cipher.clone())?;
+        return Ok(cipher.find_cracks()?.to_string());
+    }
+
+    fn part2(full_cipher: &FullCipher) -> Result<String, String> {
+        let mut cipher = MovingCipher::from_cipher(PREAMBLE, full_cipher.clone())?;
+        let crack = cipher.find_cracks()?;
+        match find_weakness(full_cipher, crack) {
+            Some(weakness) => Ok(weakness.to_string()),
+            None => Err(format!("No contiguous set adds up to {}", crack)),
+        }
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day09>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day09>(contents)
+}
+
+#[test]
+fn test_find_weakness(){
+    let content = "\
+35
+20
+15
+25
+47
+40
+62
+55
+65
+95
+102
+117
+150
+182
+127
+219
+299
+277
+309
+576
+";
+    let full_cipher = FullCipher::new(content).unwrap();
+    assert_eq!(Some(62), find_weakness(&full_cipher, 127));
+    assert_eq!(None, find_weakness(&full_cipher, 1));
+    assert_eq!(Some(2..6), weakness_range(&full_cipher.numbers, 127));
+    // 35 is in the list, but a run needs at least two numbers.
+    assert_eq!(Some(1..3), weakness_range(&full_cipher.numbers, 35));
+    assert_eq!(None, weakness_range(&[5, 1, 1], 5));
+    assert_eq!(Some(0..2), weakness_range(&[0, 5, 1], 5));
+}
+
+#[test]
+fn test_bad_cipher() {
+    assert_eq!(
+        Err(ParseError::new(3, 1, "15.5", "Could not parse number")),
+        FullCipher::new("35\n20\n15.5\n25")
+    );
+}
@@ -1,301 +1,141 @@
-use clap::{App, Arg};
-use std::fs;
-
-// FullCipher is all the numbers in the cipher to be "understood", but doesn't do the core
-// algorithm which traverses through these numbers.
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
-struct FullCipher {
-    numbers: Vec<i64>
-}
-
-impl FullCipher {
-    fn new(content: &str) -> Result<FullCipher, String> {
-        let mut numbers: Vec<i64> = Vec::new();
-        for line in content.lines() {
-            let num: i64 = match line.parse() {
-                Ok(i) => i,
-                Err(_) => return Err(format!("Could not parse {}", line))
-            };
-
-            numbers.push(num)
-        }
-
-        return Ok(FullCipher{numbers});
-    }
+use clap::{App, Arg, ArgMatches};
+use d09::stream;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+
+// The puzzle's preamble, unless --preamble says otherwise.
+const PREAMBLE: usize = 25;
+
+// app is the command line: d09 [--preamble <n>] [<numbers>].
+fn app() -> App<'static> {
+    App::new("Reader for advent")
+        .version("1.0")
+        .author("Sean Tyler Myers <seanmyers0608@gmail.com>")
+        .about("Checks an XMAS stream, one number per line")
+        .arg(
+            Arg::new("preamble")
+                .long("preamble")
+                .takes_value(true)
+                .value_name("n")
+                .help("Sets how many numbers each one is checked against"),
+        )
+        .arg(
+            Arg::new("INPUT")
+                .help("Sets the input file to use, or - for stdin")
+                .index(1),
+        )
 }
 
-
-struct MovingCipher {
-    full_cipher: FullCipher,
-
-    // These define the "sliding window" into the full cipher.
-    start_position: usize,
-    end_position: usize,
-
-    // window_size is the amount the sliding window can be. In the program, it's referred to as
-    // the "preamble", but the preamble slides along with the cipher, which makes it a window :)
-    window_size: u32,
+// Config is how long the preamble is and where to read numbers from, with None meaning stdin.
+#[derive(Debug, PartialEq)]
+struct Config {
+    preamble: usize,
+    filename: Option<String>,
 }
 
-impl MovingCipher {
-    fn new(window: u32, content: &str) -> Result<MovingCipher, String> {
-        let full_cipher = FullCipher::new(content)?;
-
-        if window > full_cipher.numbers.len() as u32 {
-            return Err("Don't support windows that large".to_string());
-        }
-
-        return Ok(MovingCipher{full_cipher, window_size: window, start_position: 0, end_position: window as usize});
-    }
-
-    fn find_cracks(& mut self) -> Result<i64, String> {
-
-        loop {
-            let next_pos = self.end_position;
-            let next_num: i64 = match self.full_cipher.numbers.get(next_pos) {
-                Some(i) => { i.clone() }
-                None => {return Err("Reached end of the line".to_string())}
-            };
-
-            let legit = self.within_pool(next_num)?;
-
-            if legit {
-                self.move_window();
-            } else {
-                return Ok(next_num)
-            }
-        }
-
-    }
-
-    fn within_pool(&self, num: i64) -> Result<bool, String> {
-        let current_pool = match self.calculate_pool(){
-            Some(p) => p,
-            None => return Err("Pool doesn't exist".to_string()),
+impl Config {
+    fn new(matches: &ArgMatches) -> Result<Config, String> {
+        let preamble = match matches.value_of("preamble") {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("{} is not a preamble length", value))?,
+            None => PREAMBLE,
         };
 
-        for i in current_pool.iter() {
-            if num == i.clone() {
-                return Ok(true);
-            }
-        }
-
-        return Ok(false);
-
-    }
-
-    fn move_window(& mut self) {
-        self.start_position += 1;
-        self.end_position += 1;
-    }
-
-    fn calculate_pool(&self) -> Option<Vec<i64>> {
-        let mut pool = Vec::new();
-
-        for i in self.start_position..self.end_position {
-            for j in i+1..self.end_position {
-                if i == j {
-                    continue;
-                }
-
-                let x = self.full_cipher.numbers.get(i)?;
-                let y = self.full_cipher.numbers.get(j)?;
-                pool.push((x+y) as i64);
-            }
-        }
-
-        return Some(pool);
-    }
-
-    fn see_window(&self) -> Vec<i64> {
-        let mut v = Vec::new();
-        for i in self.start_position..self.end_position {
-            let x = self.full_cipher.numbers.get(i).unwrap();
-            v.push(x.clone());
-        }
-        return v;
+        Ok(Config {
+            preamble,
+            filename: matches
+                .value_of("INPUT")
+                .filter(|f| *f != "-")
+                .map(|f| f.to_string()),
+        })
     }
 }
 
-#[test]
-fn test_rolling(){
-    let content = "\
-35
-20
-15
-";
-    let cipher = MovingCipher::new(3, content).unwrap();
-    assert_eq!(vec![55, 50, 35], cipher.calculate_pool().unwrap())
-
-}
-
-#[test]
-fn test_rolling_window(){
-    let content = "\
-35
-20
-15
-25
-47
-40
-62
-55
-65
-95
-102
-117
-150
-182
-127
-219
-299
-277
-309
-576
-";
-
-    let mut cipher = MovingCipher::new(5, content).unwrap();
-    println!("{}", cipher.find_cracks().unwrap());
-
-}
-
-
-struct GrowingCipher {
-    full_cipher: FullCipher,
-    size: usize,
-    start: usize,
-}
-
-impl GrowingCipher {
-     fn new(start: usize, full_cipher: FullCipher) -> Result<GrowingCipher, String> {
-        if start >= full_cipher.numbers.len() {
-            return Err("Don't support starting positions that large".to_string());
+// d09 checks an XMAS stream, one number per line, from a file or stdin. It stops reading at the
+// first invalid number and says where the weakness is, so it can sit on the end of a pipe that
+// never finishes.
+fn main() {
+    let config = Config::new(&app().get_matches()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}", err);
+        process::exit(1);
+    });
+    let name = config.filename.as_deref().unwrap_or("stdin");
+
+    let reader: Box<dyn Read> = match &config.filename {
+        Some(filename) => Box::new(File::open(filename).unwrap_or_else(|err| {
+            eprintln!("Problem reading {}: {}", filename, err);
+            process::exit(1);
+        })),
+        None => Box::new(io::stdin()),
+    };
+    let scan = stream::scan(reader, config.preamble).unwrap_or_else(|err| {
+        eprintln!("Problem reading {}: {}", name, err);
+        process::exit(1);
+    });
+
+    let invalid = match scan.invalid {
+        Some(invalid) => invalid,
+        None => {
+            println!("All {} numbers in {} are valid", scan.numbers.len(), name);
+            return;
         }
-
-        return Ok(GrowingCipher{full_cipher, size: 2, start});
-    }
-
-    fn is_contiguous(&mut self, goal: i64) -> bool {
-        loop {
-            let mut accumulator = 0;
-            
-            for i in self.start..self.start+self.size{
-                accumulator = match self.full_cipher.numbers.get(i){
-                    Some(x) => x + accumulator,
-                    None => return false,
-                }
-            }
-
-            if accumulator == goal {
-                return true;
-            }
-
-            if accumulator > goal {
-                return false;
-            }
-
-            self.size += 1;
+    };
+    println!(
+        "{}:{}: {} is not the sum of two of the {} numbers before it",
+        name, invalid.line, invalid.number, config.preamble
+    );
+    match scan.weakness() {
+        Some((range, weakness)) => println!(
+            "{}:{}-{}: weakness {}",
+            name,
+            range.start + 1,
+            range.end,
+            weakness
+        ),
+        None => {
+            println!("No contiguous set adds up to {}", invalid.number);
+            process::exit(1);
         }
     }
 }
 
-#[test]
-fn test_growing_cipher(){
-    let content = "\
-35
-20
-15
-25
-47
-40
-62
-55
-65
-95
-102
-117
-150
-182
-127
-219
-299
-277
-309
-576
-";
-    let full_cipher = FullCipher::new(content).unwrap();
-    
-    for i in 0..full_cipher.numbers.len(){
-        let mut cipher = GrowingCipher::new(i, full_cipher.clone()).unwrap();
-        if cipher.is_contiguous(127) {
-            println!("WE GOTTA {}, {}", cipher.start, cipher.size);
-            let mut smallest = full_cipher.numbers[cipher.start];
-            let mut largest = full_cipher.numbers[cipher.start];
-
-
-            for index in cipher.start..cipher.start+cipher.size {
-                if full_cipher.numbers[index] > largest {
-                    largest = full_cipher.numbers[index]
-                }
-
-                if full_cipher.numbers[index] < smallest {
-                    smallest = full_cipher.numbers[index]
-                }
-            }
-
-            println!("With my numbers combined: {}, {}, {}", smallest, largest, smallest+largest);
-            break;
-        }
-    }
-    
-
+#[cfg(test)]
+fn config(args: &[&str]) -> Result<Config, String> {
+    let matches = app()
+        .try_get_matches_from(std::iter::once("d09").chain(args.iter().copied()))
+        .map_err(|err| err.to_string())?;
+    Config::new(&matches)
 }
 
-
-fn main() {
-    let matches = App::new("Reader for advent")
-        .version("1.0")
-        .author("Sean Tyler Myers <seanmyers0608@gmail.com>")
-        .about("Reads advent calendar programs")
-        .arg(
-            Arg::new("INPUT")
-                .about("Sets the input file to use")
-                .required(true)
-                .index(1),
-        )
-        .get_matches();
-
-    let txt_location: &str = matches.value_of("INPUT").unwrap();
-    let contents = fs::read_to_string(txt_location).unwrap();
-    let mut cipher = MovingCipher::new(25, &contents).unwrap();
-    let s = cipher.find_cracks().unwrap();
-    println!("{}", s);
-
-    let full_cipher = FullCipher::new(&contents).unwrap();
-    
-    for i in 0..full_cipher.numbers.len(){
-        let mut cipher = GrowingCipher::new(i, full_cipher.clone()).unwrap();
-        if cipher.is_contiguous(s) {
-            println!("The strand {}, {}", cipher.start, cipher.size);
-            let mut smallest = full_cipher.numbers[cipher.start];
-            let mut largest = full_cipher.numbers[cipher.start];
-
-
-            for index in cipher.start..cipher.start+cipher.size {
-                println!("Number: {}", full_cipher.numbers[index]);
-                if full_cipher.numbers[index] > largest {
-                    largest = full_cipher.numbers[index]
-                }
-
-                if full_cipher.numbers[index] < smallest {
-                    smallest = full_cipher.numbers[index]
-                }
-            }
-
-            println!("With my numbers combined: {}, {}, {}", smallest, largest, smallest+largest);
-            break;
-        }
-    }
-    
-
+#[test]
+fn test_config() {
+    assert_eq!(
+        Ok(Config {
+            preamble: 25,
+            filename: None
+        }),
+        config(&[])
+    );
+    assert_eq!(
+        Ok(Config {
+            preamble: 5,
+            filename: Some("numbers.txt".to_string())
+        }),
+        config(&["--preamble", "5", "numbers.txt"])
+    );
+    assert_eq!(
+        Ok(Config {
+            preamble: 5,
+            filename: None
+        }),
+        config(&["-", "--preamble=5"])
+    );
+
+    assert_eq!(
+        Err("five is not a preamble length".to_string()),
+        config(&["--preamble", "five"])
+    );
+    assert!(config(&["a", "b"]).is_err());
 }
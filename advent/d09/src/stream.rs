@@ -0,0 +1,197 @@
+use common::ParseError;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+
+// Validator checks numbers one at a time as they arrive, only ever holding on to the last preamble
+// of them. The first preamble numbers are always valid, and every number after that has to be the
+// sum of two different numbers in the window before it.
+#[derive(Debug, Clone)]
+pub struct Validator {
+    preamble: usize,
+    window: VecDeque<i64>,
+    // How many times each number is in the window, so a check doesn't have to look at every pair.
+    counts: HashMap<i64, usize>,
+}
+
+impl Validator {
+    pub fn new(preamble: usize) -> Result<Validator, String> {
+        if preamble < 2 {
+            return Err(format!(
+                "A preamble of {} can't have two numbers to add up",
+                preamble
+            ));
+        }
+        Ok(Validator {
+            preamble,
+            window: VecDeque::with_capacity(preamble + 1),
+            counts: HashMap::new(),
+        })
+    }
+
+    // is_sum says whether num is the sum of two different numbers in the window. The same value
+    // twice only counts if it's in the window twice.
+    fn is_sum(&self, num: i64) -> bool {
+        self.window.iter().any(|x| match num.checked_sub(*x) {
+            Some(y) if y == *x => self.counts.get(&y).copied().unwrap_or(0) >= 2,
+            Some(y) => self.counts.contains_key(&y),
+            None => false,
+        })
+    }
+
+    // check says whether num is valid, and slides it into the window either way.
+    pub fn check(&mut self, num: i64) -> bool {
+        let valid = self.window.len() < self.preamble || self.is_sum(num);
+
+        self.window.push_back(num);
+        *self.counts.entry(num).or_insert(0) += 1;
+        if self.window.len() > self.preamble {
+            let old = self.window.pop_front().unwrap();
+            let count = self.counts.get_mut(&old).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&old);
+            }
+        }
+
+        valid
+    }
+}
+
+// Invalid is the first number in a stream that isn't the sum of two in the window before it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Invalid {
+    // The line it was on, counting from 1.
+    pub line: usize,
+    pub number: i64,
+}
+
+// Scan is everything read from a stream, up to and including the first invalid number if there is
+// one.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Scan {
+    pub numbers: Vec<i64>,
+    pub invalid: Option<Invalid>,
+}
+
+impl Scan {
+    // weakness is the range of numbers adding up to the invalid one, and the smallest and largest
+    // of them added together. It's only looked for in the numbers before the invalid one, as
+    // nothing after it was read.
+    pub fn weakness(&self) -> Option<(std::ops::Range<usize>, i64)> {
+        let invalid = self.invalid?;
+        let before = &self.numbers[..self.numbers.len() - 1];
+        let range = crate::weakness_range(before, invalid.number)?;
+        let strand = &before[range.clone()];
+        Some((range, strand.iter().min()? + strand.iter().max()?))
+    }
+}
+
+// scan reads numbers from reader, one per line, and stops as soon as one of them is invalid, so
+// the rest of the stream is never read. A stream that's valid all the way through is read to the
+// end.
+pub fn scan<R: Read>(reader: R, preamble: usize) -> Result<Scan, String> {
+    let mut validator = Validator::new(preamble)?;
+    let mut numbers = Vec::new();
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.map_err(|err| format!("Could not read line {}: {}", i + 1, err))?;
+        let number: i64 = line
+            .parse()
+            .map_err(|_| ParseError::new(i + 1, 1, &line, "Could not parse number"))?;
+
+        numbers.push(number);
+        if !validator.check(number) {
+            return Ok(Scan {
+                numbers,
+                invalid: Some(Invalid {
+                    line: i + 1,
+                    number,
+                }),
+            });
+        }
+    }
+
+    Ok(Scan {
+        numbers,
+        invalid: None,
+    })
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+35
+20
+15
+25
+47
+40
+62
+55
+65
+95
+102
+117
+150
+182
+127
+219
+299
+277
+309
+576
+";
+
+#[test]
+fn test_validator() {
+    let mut validator = Validator::new(25).unwrap();
+    for n in 1..=25 {
+        assert!(validator.check(n));
+    }
+    assert!(validator.check(26));
+    assert!(validator.check(49));
+    assert!(!validator.check(100));
+    // An invalid number still slides into the window.
+    assert!(validator.check(149));
+
+    let mut validator = Validator::new(2).unwrap();
+    assert!(validator.check(5));
+    assert!(validator.check(5));
+    assert!(validator.check(10));
+    assert!(!validator.check(10));
+
+    assert!(Validator::new(1).is_err());
+}
+
+#[test]
+fn test_scan() {
+    let scan = scan(EXAMPLE.as_bytes(), 5).unwrap();
+    assert_eq!(
+        Some(Invalid {
+            line: 15,
+            number: 127
+        }),
+        scan.invalid
+    );
+    assert_eq!(15, scan.numbers.len());
+    assert_eq!(Some((2..6, 62)), scan.weakness());
+}
+
+#[test]
+fn test_scan_stops_at_invalid() {
+    // Nothing after the invalid number gets read, so the text that isn't a number doesn't matter.
+    let result = scan("1\n2\n4\nnot a number\n".as_bytes(), 2).unwrap();
+    assert_eq!(Some(Invalid { line: 3, number: 4 }), result.invalid);
+
+    assert_eq!(
+        Err("line 4, column 1: Could not parse number, found \"x\"".to_string()),
+        scan("1\n2\n3\nx\n".as_bytes(), 2)
+    );
+}
+
+#[test]
+fn test_scan_all_valid() {
+    let scan = scan("1\n2\n3\n5\n8".as_bytes(), 2).unwrap();
+    assert_eq!(None, scan.invalid);
+    assert_eq!(None, scan.weakness());
+    assert_eq!(vec![1, 2, 3, 5, 8], scan.numbers);
+}
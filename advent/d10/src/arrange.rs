@@ -0,0 +1,256 @@
+use crate::JoltSolver;
+use num_bigint::BigUint;
+
+// Arrangements is every way of chaining a solver's adapters from the outlet to the device. They're
+// counted rather than listed, with no limit on how many there are, and numbered in order so any
+// one of them can be picked out without going through the ones before it.
+#[derive(Debug, Clone)]
+pub struct Arrangements<'a> {
+    solver: &'a JoltSolver,
+    // ways[n] is how many ways there are to get from adapter n to the device.
+    ways: Vec<BigUint>,
+}
+
+impl<'a> Arrangements<'a> {
+    pub fn new(solver: &'a JoltSolver) -> Arrangements<'a> {
+        let len = solver.adapters.len();
+        let mut ways = vec![BigUint::from(0u32); len];
+        ways[len - 1] = BigUint::from(1u32);
+        for n in (0..len - 1).rev() {
+            let mut total = BigUint::from(0u32);
+            for next in Arrangements::next(solver, n) {
+                total += &ways[next];
+            }
+            ways[n] = total;
+        }
+        Arrangements { solver, ways }
+    }
+
+    // next is every adapter that can go straight after adapter n.
+    fn next(solver: &JoltSolver, n: usize) -> impl Iterator<Item = usize> + '_ {
+        let rating = solver.adapters[n].rating;
+        (n + 1..solver.adapters.len())
+            .take_while(move |m| solver.adapters[*m].rating - rating <= *solver.jumps.end())
+            .filter(move |m| solver.adapters[*m].rating - rating >= *solver.jumps.start())
+    }
+
+    pub fn count(&self) -> &BigUint {
+        &self.ways[0]
+    }
+
+    // nth is the ratings of the nth arrangement, counting from 0, or None if there aren't that
+    // many. Arrangements are in order of the adapters they use, earliest first.
+    pub fn nth(&self, n: &BigUint) -> Option<Vec<i32>> {
+        if n >= self.count() {
+            return None;
+        }
+
+        let mut n = n.clone();
+        let mut at = 0;
+        let mut ratings = vec![self.solver.adapters[0].rating];
+        while at < self.ways.len() - 1 {
+            for next in Arrangements::next(self.solver, at) {
+                if n < self.ways[next] {
+                    at = next;
+                    break;
+                }
+                n -= &self.ways[next];
+            }
+            ratings.push(self.solver.adapters[at].rating);
+        }
+        Some(ratings)
+    }
+
+    // sample is count arrangements spread evenly from the first to the last, or all of them if
+    // there aren't that many.
+    pub fn sample(&self, count: usize) -> Vec<Vec<i32>> {
+        if BigUint::from(count) >= *self.count() {
+            return self.iter().collect();
+        }
+        if count == 1 {
+            return self.nth(&BigUint::from(0u32)).into_iter().collect();
+        }
+
+        let last = self.count() - 1u32;
+        (0..count)
+            .filter_map(|i| self.nth(&(&last * i / (count - 1))))
+            .collect()
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            arrangements: self,
+            path: Vec::new(),
+            done: self.ways[0] == BigUint::from(0u32),
+        }
+    }
+}
+
+// Iter goes through every arrangement in order without counting them off one by one, by stepping
+// to the next adapter along from the end of the last arrangement.
+pub struct Iter<'a> {
+    arrangements: &'a Arrangements<'a>,
+    // The adapters in the last arrangement, by index.
+    path: Vec<usize>,
+    done: bool,
+}
+
+impl<'a> Iter<'a> {
+    // first_from is the earliest way to the device that can follow adapter n.
+    fn first_from(&self, n: usize) -> Option<usize> {
+        Arrangements::next(self.arrangements.solver, n)
+            .find(|m| self.arrangements.ways[*m] != BigUint::from(0u32))
+    }
+
+    // finish takes the earliest way from the end of path to the device.
+    fn finish(&mut self) {
+        let end = self.arrangements.ways.len() - 1;
+        while *self.path.last().unwrap() != end {
+            let next = self.first_from(*self.path.last().unwrap()).unwrap();
+            self.path.push(next);
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Vec<i32>;
+
+    fn next(&mut self) -> Option<Vec<i32>> {
+        if self.done {
+            return None;
+        }
+
+        if self.path.is_empty() {
+            self.path.push(0);
+        } else {
+            // Back up to the last adapter with a later way to the device after it, and take that.
+            loop {
+                let last = match self.path.pop() {
+                    Some(last) => last,
+                    None => {
+                        self.done = true;
+                        return None;
+                    }
+                };
+                let before = match self.path.last() {
+                    Some(before) => *before,
+                    None => {
+                        self.done = true;
+                        return None;
+                    }
+                };
+                let later = Arrangements::next(self.arrangements.solver, before)
+                    .find(|m| *m > last && self.arrangements.ways[*m] != BigUint::from(0u32));
+                if let Some(later) = later {
+                    self.path.push(later);
+                    break;
+                }
+            }
+        }
+        self.finish();
+
+        let adapters = &self.arrangements.solver.adapters;
+        Some(self.path.iter().map(|n| adapters[*n].rating).collect())
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4
+";
+
+#[test]
+fn test_count() {
+    let solver = JoltSolver::parse(EXAMPLE.to_owned(), 0).unwrap();
+    assert_eq!(&BigUint::from(8u32), Arrangements::new(&solver).count());
+
+    // With jumps of up to 5 there are more ways through, and the device is 5 above the highest.
+    let solver = JoltSolver::parse_with_jumps(EXAMPLE.to_owned(), 0, 1..=5).unwrap();
+    assert_eq!(24, solver.adapters.last().unwrap().rating);
+    let arrangements = Arrangements::new(&solver);
+    assert_eq!(
+        BigUint::from(solver.solve2().unwrap()),
+        *arrangements.count()
+    );
+    assert_eq!(&BigUint::from(423u32), arrangements.count());
+    assert_eq!(423, arrangements.iter().count());
+}
+
+#[test]
+fn test_count_past_u128() {
+    // Every adapter from 1 to 200, where any of them can go after any other. The device is only in
+    // reach of the highest, but every other adapter is either used or not, which is 2^199 ways.
+    let contents: Vec<String> = (1..=200).map(|n| n.to_string()).collect();
+    let solver = JoltSolver::parse_with_jumps(contents.join("\n"), 0, 1..=200).unwrap();
+    assert!(solver.solve2().is_err());
+    assert_eq!(
+        BigUint::from(1u32) << 199usize,
+        *Arrangements::new(&solver).count()
+    );
+}
+
+#[test]
+fn test_enumerate() {
+    let solver = JoltSolver::parse(EXAMPLE.to_owned(), 0).unwrap();
+    let arrangements = Arrangements::new(&solver);
+    let all: Vec<Vec<i32>> = arrangements.iter().collect();
+    assert_eq!(8, all.len());
+    assert_eq!(vec![0, 1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19, 22], all[0]);
+    assert_eq!(vec![0, 1, 4, 7, 10, 12, 15, 16, 19, 22], all[7]);
+    for (n, arrangement) in all.iter().enumerate() {
+        assert_eq!(
+            Some(arrangement.clone()),
+            arrangements.nth(&BigUint::from(n))
+        );
+    }
+    assert_eq!(None, arrangements.nth(&BigUint::from(8u32)));
+}
+
+#[test]
+fn test_sample() {
+    let solver = JoltSolver::parse(EXAMPLE.to_owned(), 0).unwrap();
+    let arrangements = Arrangements::new(&solver);
+    let all: Vec<Vec<i32>> = arrangements.iter().collect();
+    assert_eq!(
+        vec![all[0].clone(), all[3].clone(), all[7].clone()],
+        arrangements.sample(3)
+    );
+    assert_eq!(all, arrangements.sample(100));
+    assert_eq!(vec![all[0].clone()], arrangements.sample(1));
+    assert!(arrangements.sample(0).is_empty());
+}
+
+#[test]
+fn test_smallest_jump() {
+    let solver = JoltSolver::parse_with_jumps("1\n2\n3\n4\n5\n6".to_owned(), 0, 2..=3).unwrap();
+    let arrangements = Arrangements::new(&solver);
+    assert_eq!(&BigUint::from(2u32), arrangements.count());
+    assert_eq!(
+        vec![vec![0, 2, 4, 6, 9], vec![0, 3, 6, 9]],
+        arrangements.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(vec![0, 3, 6, 9]),
+        arrangements.nth(&BigUint::from(1u32))
+    );
+}
+
+#[test]
+fn test_no_arrangements() {
+    // Nothing gets from 1 jolt to 5.
+    let solver = JoltSolver::parse("1\n5".to_owned(), 0).unwrap();
+    let arrangements = Arrangements::new(&solver);
+    assert_eq!(&BigUint::from(0u32), arrangements.count());
+    assert_eq!(0, arrangements.iter().count());
+    assert!(arrangements.sample(2).is_empty());
+}
@@ -0,0 +1,361 @@
+use common::ParseError;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+pub mod arrange;
+
+#[derive(Debug, PartialEq, PartialOrd, Copy, Clone, Eq, Ord)]
+pub struct JoltAdapter {
+    pub rating: i32,
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Eq, Ord)]
+pub struct JoltLeap {
+    pub amount: i32
+}
+
+impl JoltAdapter {
+    pub fn new(rating: i32) -> JoltAdapter{
+        return JoltAdapter{rating};
+    }
+
+    // jumps is how far below the rating the incoming jolts are allowed to be.
+    pub fn handle(&self, incoming: i32, jumps: &RangeInclusive<i32>) -> Option<JoltLeap> {
+        if jumps.contains(&(self.rating - incoming)) {
+            return Option::Some(JoltLeap{amount: self.rating - incoming})
+        }
+
+        return Option::None;
+    }
+}
+
+#[test]
+fn test_jolt_capacitance(){
+    let adapter = JoltAdapter::new(4);
+    assert_eq!(Option::Some(JoltLeap{amount: 3}), adapter.handle(1, &(1..=3)));
+    assert_eq!(Option::None, adapter.handle(0, &(1..=3)));
+    assert_eq!(Option::None, adapter.handle(3, &(2..=3)));
+    assert_eq!(Option::Some(JoltLeap{amount: 2}), adapter.handle(2, &(2..=3)));
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct JoltSolver {
+    pub adapters: Vec<JoltAdapter>,
+    pub start: i32,
+    // jumps is how much the jolts can go up by from one adapter to the next. The device is the
+    // biggest jump above the highest adapter.
+    pub jumps: RangeInclusive<i32>,
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct JoltSolution1 {
+    pub path: Vec<JoltLeap>,
+    pub jolt_3: i32,
+    pub jolt_1: i32,
+    // gaps counts how many leaps there are of every size.
+    pub gaps: BTreeMap<i32, i32>,
+}
+
+impl JoltSolution1 {
+    pub fn new() -> JoltSolution1 {
+        JoltSolution1{
+            path: Vec::new(),
+            jolt_1: 0,
+            jolt_3: 0,
+            gaps: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, leap: JoltLeap) {
+        if leap.amount == 3 {
+            self.jolt_3 += 1;
+        }
+
+        if leap.amount == 1 {
+            self.jolt_1 += 1;
+        }
+
+        *self.gaps.entry(leap.amount).or_insert(0) += 1;
+        self.path.push(leap);
+    }
+}
+
+impl JoltSolver {
+    pub fn parse(contents: String, current: i32) -> Result<JoltSolver, ParseError>{
+        JoltSolver::parse_with_jumps(contents, current, 1..=3)
+    }
+
+    // parse_with_jumps is parse for adapters that take jumps anywhere in jumps, rather than 1 to 3
+    // jolts.
+    pub fn parse_with_jumps(contents: String, current: i32, jumps: RangeInclusive<i32>) -> Result<JoltSolver, ParseError>{
+        let mut adapters: Vec<JoltAdapter> = Vec::new();
+        adapters.push(JoltAdapter::new(0));
+        for x in contents.lines() {
+            let rating = match x.parse::<i32>() {
+                Ok(r) => r,
+                Err(_) => return Err(ParseError::at(&contents, x, "Could not parse adapter rating")),
+            };
+            let adapter = JoltAdapter::new(rating);
+            adapters.push(adapter);
+        }
+
+        adapters.sort();
+        let highest_device = adapters[adapters.len()-1];
+        adapters.push(JoltAdapter{rating: highest_device.rating+jumps.end()});
+
+        return Ok(JoltSolver{
+            adapters,
+            start: current,
+            jumps,
+        })
+    }
+
+    pub fn solve(&self) -> Result<JoltSolution1, String> {
+        let mut current = self.start;
+        let mut solution = JoltSolution1::new();
+        // The first adapter is the outlet, which is where the chain starts rather than a leap.
+        for x in self.adapters.iter().skip(1) {
+            let leap = x.handle(current, &self.jumps);
+
+            let real_leap = match leap {
+                Some(x) => x,
+                None => return Result::Err(format!("No adapter gets from {} jolts to {}", current, x.rating))
+            };
+
+            current = x.rating;
+            solution.add(real_leap)
+
+        }
+        return Result::Ok(solution);
+    }
+
+    // solve2 counts the arrangements, or gives up once there are more than a u128 holds. The
+    // arrange module counts past that.
+    pub fn solve2(&self) -> Result<u128, String> {
+        let mut counts: Vec<u128> = Vec::new();
+        counts.resize(self.adapters.len(), 0);
+        counts[0] = 1;
+        for x in 0..self.adapters.len(){
+            for y in x+1..self.adapters.len() {
+                let jump = self.adapters[y].rating - self.adapters[x].rating;
+                if jump > *self.jumps.end() {
+                    break;
+                }
+                if jump < *self.jumps.start() {
+                    continue;
+                }
+                counts[y] = match counts[y].checked_add(counts[x]) {
+                    Some(count) => count,
+                    None => return Result::Err("There are too many arrangements to count in a u128".to_string()),
+                };
+            }
+        }
+
+        return Result::Ok(counts[self.adapters.len()-1])
+    }
+}
+
+// Day10 is the bag of joltage adapters.
+pub struct Day10;
+
+impl common::Solver for Day10 {
+    type Input<'a> = JoltSolver;
+
+    fn parse(contents: &str) -> Result<JoltSolver, ParseError> {
+        JoltSolver::parse(contents.to_owned(), 0)
+    }
+
+    fn part1(solver: &JoltSolver) -> Result<String, String> {
+        let solution = solver.solve()?;
+        return Ok((solution.jolt_1 * solution.jolt_3).to_string());
+    }
+
+    fn part2(solver: &JoltSolver) -> Result<String, String> {
+        return Ok(arrange::Arrangements::new(solver).count().to_string());
+    }
+}
+
+pub fn part1(contents: &str) -> Result<String, String> {
+    common::part1::<Day10>(contents)
+}
+
+pub fn part2(contents: &str) -> Result<String, String> {
+    common::part2::<Day10>(contents)
+}
+
+#[test]
+fn test_jolt_solver(){
+    let content = "\
+16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4
+";
+    let solver = JoltSolver::parse(content.to_owned(), 0).unwrap();
+    assert_eq!(vec![
+        JoltAdapter::new(0),
+        JoltAdapter::new(1),
+        JoltAdapter::new(4),
+        JoltAdapter::new(5),
+        JoltAdapter::new(6),
+        JoltAdapter::new(7),
+        JoltAdapter::new(10),
+        JoltAdapter::new(11),
+        JoltAdapter::new(12),
+        JoltAdapter::new(15),
+        JoltAdapter::new(16),
+        JoltAdapter::new(19),
+        // Device adapter itself, it's never in the actual list.
+        JoltAdapter::new(22),
+    ], solver.adapters);
+
+    let result = solver.solve().unwrap();
+
+    assert_eq!(7, result.jolt_1);
+    assert_eq!(5, result.jolt_3);
+}
+
+#[test]
+fn test_jolt_solver_part2(){
+    let content = "\
+16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4
+";
+    let solver = JoltSolver::parse(content.to_owned(), 0).unwrap();
+    let result = solver.solve2().unwrap();
+    let len = result;
+    assert_eq!(8, len);
+}
+
+
+#[test]
+fn test_jolt_solver_longer(){
+    let content = "\
+28
+33
+18
+42
+31
+14
+46
+20
+48
+47
+24
+23
+49
+45
+19
+38
+39
+11
+1
+32
+25
+35
+8
+17
+7
+9
+4
+2
+34
+10
+3
+";
+    let solver = JoltSolver::parse(content.to_owned(), 0).unwrap();
+    let result = solver.solve().unwrap();
+
+    assert_eq!(22, result.jolt_1);
+    assert_eq!(10, result.jolt_3);
+    assert_eq!(vec![(1, 22), (3, 10)], result.gaps.into_iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_jolt_capacitance_range(){
+    let solver = JoltSolver::parse_with_jumps("4\n1\n8\n6".to_owned(), 0, 1..=4).unwrap();
+    let result = solver.solve().unwrap();
+    assert_eq!(vec![(1, 1), (2, 2), (3, 1), (4, 1)], result.gaps.into_iter().collect::<Vec<_>>());
+    // 0 -> 4 and 4 -> 8 are both fine, so 1 and 6 are optional.
+    assert_eq!(Ok(4), solver.solve2());
+
+    let solver = JoltSolver::parse_with_jumps("4\n1\n8\n6".to_owned(), 0, 1..=2).unwrap();
+    assert_eq!(Err("No adapter gets from 1 jolts to 4".to_string()), solver.solve());
+    assert_eq!(Ok(0), solver.solve2());
+}
+
+#[test]
+fn test_jolt_smallest_jump(){
+    // Jumps of 2 or 3 can't use every adapter, and get to 6 as 2+2+2 or 3+3 before the device at 9.
+    let solver = JoltSolver::parse_with_jumps("1\n2\n3\n4\n5\n6".to_owned(), 0, 2..=3).unwrap();
+    assert_eq!(9, solver.adapters.last().unwrap().rating);
+    assert_eq!(Err("No adapter gets from 0 jolts to 1".to_string()), solver.solve());
+    assert_eq!(Ok(2), solver.solve2());
+}
+
+#[test]
+fn test_jolt_solver2_longer(){
+    let content = "\
+28
+33
+18
+42
+31
+14
+46
+20
+48
+47
+24
+23
+49
+45
+19
+38
+39
+11
+1
+32
+25
+35
+8
+17
+7
+9
+4
+2
+34
+10
+3
+";
+    let solver = JoltSolver::parse(content.to_owned(), 0).unwrap();
+    let result = solver.solve2().unwrap();
+
+    assert_eq!(19208, result);
+}
+
+
+
+#[test]
+fn test_bad_adapters() {
+    assert_eq!(
+        Err(ParseError::new(2, 1, "4 jolts", "Could not parse adapter rating")),
+        JoltSolver::parse("1\n4 jolts\n5".to_owned(), 0)
+    );
+}
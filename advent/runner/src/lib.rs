@@ -0,0 +1,26 @@
+// A Part solves one half of a day's puzzle from the raw puzzle input.
+pub type Part = fn(&str) -> Result<String, String>;
+
+// DAYS is every day we have a solver for, part 1 then part 2.
+pub const DAYS: [(Part, Part); 10] = [
+    (d01::part1, d01::part2),
+    (d02::part1, d02::part2),
+    (d03::part1, d03::part2),
+    (d04::part1, d04::part2),
+    (d05::part1, d05::part2),
+    (d06::part1, d06::part2),
+    (d07::part1, d07::part2),
+    (d08::part1, d08::part2),
+    (d09::part1, d09::part2),
+    (d10::part1, d10::part2),
+];
+
+// solver is the solver for one part of a day, if we have one.
+pub fn solver(day: u8, part: u8) -> Option<Part> {
+    let (part1, part2) = DAYS.get(usize::from(day).checked_sub(1)?)?;
+    match part {
+        1 => Some(*part1),
+        2 => Some(*part2),
+        _ => None,
+    }
+}
@@ -0,0 +1,295 @@
+use clap::{App, Arg, ArgMatches};
+use common::{ParseError, Solver};
+use std::fs;
+use std::process;
+use timing::Timings;
+
+mod timing;
+
+// app is the command line: advent [--bench <runs>] <day> [part] <input>.
+fn app() -> App<'static> {
+    App::new("Advent runner")
+        .version("1.0")
+        .author("Sean Tyler Myers <seanmyers0608@gmail.com>")
+        .about("Solves a day of advent, timing each step")
+        .arg(
+            Arg::new("bench")
+                .long("bench")
+                .takes_value(true)
+                .value_name("runs")
+                .help("Solves everything this many times and averages the timings"),
+        )
+        .arg(
+            Arg::new("DAY")
+                .help("Sets the day to solve")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::new("INPUT")
+                .help("Sets the part to solve, if only one, then the input file to use")
+                .required(true)
+                .min_values(1)
+                .max_values(2)
+                .index(2),
+        )
+}
+
+// Config is what to solve. Without a part, both parts are solved. With --bench, everything is run
+// that many times and the timings averaged.
+#[derive(Debug, PartialEq)]
+struct Config {
+    day: u8,
+    part: Option<u8>,
+    filename: String,
+    runs: u32,
+}
+
+impl Config {
+    fn new(matches: &ArgMatches) -> Result<Config, String> {
+        let runs = match matches.value_of("bench") {
+            Some(bench) => match bench.parse() {
+                Ok(r) if r > 0 => r,
+                _ => return Err(format!("{} is not a number of runs", bench)),
+            },
+            None => 1,
+        };
+
+        let day = matches.value_of("DAY").unwrap();
+        let day = match day.parse() {
+            Ok(d) if runner(d).is_some() => d,
+            _ => return Err(format!("No solver for day {}", day)),
+        };
+
+        let input: Vec<&str> = matches.values_of("INPUT").unwrap().collect();
+        let (part, filename) = match input.as_slice() {
+            [part, filename] => match part.parse() {
+                Ok(p) if p == 1 || p == 2 => (Some(p), filename),
+                _ => return Err(format!("There is no part {}", part)),
+            },
+            [filename] => (None, filename),
+            _ => unreachable!("clap takes one or two values"),
+        };
+
+        Ok(Config {
+            day,
+            part,
+            filename: filename.to_string(),
+            runs,
+        })
+    }
+}
+
+// Problem is why a day couldn't be solved: either the input didn't parse, or a part had no answer.
+#[derive(Debug, PartialEq)]
+enum Problem {
+    Parse(ParseError),
+    Solve(String),
+}
+
+impl From<ParseError> for Problem {
+    fn from(err: ParseError) -> Problem {
+        Problem::Parse(err)
+    }
+}
+
+impl From<String> for Problem {
+    fn from(err: String) -> Problem {
+        Problem::Solve(err)
+    }
+}
+
+// excerpt quotes the line err is on, underlining the offending text.
+fn excerpt(err: &ParseError, contents: &str) -> String {
+    let line = contents
+        .lines()
+        .nth(err.line.saturating_sub(1))
+        .unwrap_or("");
+    let width = err.text.lines().next().unwrap_or("").chars().count().max(1);
+    format!(
+        "    {}\n    {}{}",
+        line,
+        " ".repeat(err.column.saturating_sub(1)),
+        "^".repeat(width)
+    )
+}
+
+// run solves the parts of S that config asks for, timing each step.
+fn run<S: Solver>(config: &Config, contents: &str) -> Result<(), Problem> {
+    let mut parse = Timings::default();
+    let mut part1 = Timings::default();
+    let mut part2 = Timings::default();
+    let mut answers = (None, None);
+
+    for _ in 0..config.runs {
+        let input = parse.time(|| S::parse(contents))?;
+        if config.part != Some(2) {
+            answers.0 = Some(part1.time(|| S::part1(&input))?);
+        }
+        if config.part != Some(1) {
+            answers.1 = Some(part2.time(|| S::part2(&input))?);
+        }
+    }
+
+    if let Some(answer) = answers.0 {
+        println!("Part 1: {}", answer);
+    }
+    if let Some(answer) = answers.1 {
+        println!("Part 2: {}", answer);
+    }
+    println!("Parse took {}", parse);
+    if config.part != Some(2) {
+        println!("Part 1 took {}", part1);
+    }
+    if config.part != Some(1) {
+        println!("Part 2 took {}", part2);
+    }
+    Ok(())
+}
+
+// A Runner solves one day's puzzle the way config asks.
+type Runner = fn(&Config, &str) -> Result<(), Problem>;
+
+// RUNNERS is every day we have a solver for, in order.
+const RUNNERS: [Runner; 10] = [
+    run::<d01::Day01>,
+    run::<d02::Day02>,
+    run::<d03::Day03>,
+    run::<d04::Day04>,
+    run::<d05::Day05>,
+    run::<d06::Day06>,
+    run::<d07::Day07>,
+    run::<d08::Day08>,
+    run::<d09::Day09>,
+    run::<d10::Day10>,
+];
+
+// runner is the runner for a day, if we have one.
+fn runner(day: u8) -> Option<Runner> {
+    RUNNERS.get(usize::from(day).checked_sub(1)?).copied()
+}
+
+fn main() {
+    let matches = app().get_matches();
+    let config = Config::new(&matches).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}", err);
+        process::exit(1);
+    });
+
+    let contents = fs::read_to_string(&config.filename).unwrap_or_else(|err| {
+        eprintln!("Problem reading {}: {}", config.filename, err);
+        process::exit(1);
+    });
+
+    let result = match runner(config.day) {
+        Some(run) => run(&config, &contents),
+        None => Err(Problem::Solve(format!("No solver for day {}", config.day))),
+    };
+
+    match result {
+        Ok(()) => {}
+        Err(Problem::Parse(err)) => {
+            eprintln!("Problem parsing {}", err.clone().in_file(&config.filename));
+            eprintln!("{}", excerpt(&err, &contents));
+            process::exit(1);
+        }
+        Err(Problem::Solve(err)) => {
+            eprintln!("Problem solving day {}: {}", config.day, err);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+fn config(args: &[&str]) -> Result<Config, String> {
+    let matches = app()
+        .try_get_matches_from(std::iter::once("advent").chain(args.iter().copied()))
+        .map_err(|err| err.to_string())?;
+    Config::new(&matches)
+}
+
+#[test]
+fn test_config() {
+    assert_eq!(
+        Ok(Config {
+            day: 7,
+            part: None,
+            filename: "input.txt".to_string(),
+            runs: 1
+        }),
+        config(&["7", "input.txt"])
+    );
+    assert_eq!(
+        Ok(Config {
+            day: 10,
+            part: Some(2),
+            filename: "input.txt".to_string(),
+            runs: 1
+        }),
+        config(&["10", "2", "input.txt"])
+    );
+    assert_eq!(
+        Ok(Config {
+            day: 9,
+            part: Some(1),
+            filename: "input.txt".to_string(),
+            runs: 50
+        }),
+        config(&["--bench", "50", "9", "1", "input.txt"])
+    );
+    assert_eq!(
+        Ok(50),
+        config(&["9", "input.txt", "--bench=50"]).map(|c| c.runs)
+    );
+}
+
+#[test]
+fn test_bad_config() {
+    assert!(config(&["7"]).is_err());
+    assert!(config(&["7", "1", "2", "input.txt"]).is_err());
+    assert!(config(&["1", "input.txt", "--bench"]).is_err());
+    assert_eq!(
+        Err("No solver for day 11".to_string()),
+        config(&["11", "input.txt"])
+    );
+    assert_eq!(
+        Err("There is no part 3".to_string()),
+        config(&["1", "3", "input.txt"])
+    );
+    assert_eq!(
+        Err("0 is not a number of runs".to_string()),
+        config(&["--bench", "0", "1", "input.txt"])
+    );
+}
+
+#[test]
+fn test_runner() {
+    assert!(runner(0).is_none());
+    assert!(runner(1).is_some());
+    assert!(runner(10).is_some());
+    assert!(runner(11).is_none());
+}
+
+#[test]
+fn test_excerpt() {
+    let contents = "1721\n97x\n366";
+    let err = ParseError::new(2, 3, "x", "Could not parse number");
+    assert_eq!("    97x\n      ^", excerpt(&err, contents));
+
+    let err = ParseError::new(3, 1, "366", "Could not parse number");
+    assert_eq!("    366\n    ^^^", excerpt(&err, contents));
+}
+
+#[test]
+fn test_run_reports_parse_errors() {
+    let config = config(&["1", "input.txt"]).unwrap();
+    assert_eq!(
+        Err(Problem::Parse(ParseError::new(
+            2,
+            1,
+            "97x",
+            "Could not parse number"
+        ))),
+        run::<d01::Day01>(&config, "1721\n97x\n366")
+    );
+}
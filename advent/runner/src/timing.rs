@@ -0,0 +1,85 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// Timings is every run of one step, like parsing or part 1.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Timings {
+    pub samples: Vec<Duration>,
+}
+
+impl Timings {
+    // time runs f, keeping how long it took.
+    pub fn time<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.samples.push(start.elapsed());
+        result
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let total: Duration = self.samples.iter().sum();
+        Some(total / self.samples.len() as u32)
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.samples.iter().min().copied()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+}
+
+// A single run only has the one time to show, more than that gets the mean, min and max.
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.mean(), self.min(), self.max()) {
+            (Some(mean), _, _) if self.samples.len() == 1 => write!(f, "{:?}", mean),
+            (Some(mean), Some(min), Some(max)) => write!(
+                f,
+                "mean {:?}, min {:?}, max {:?} over {} runs",
+                mean,
+                min,
+                max,
+                self.samples.len()
+            ),
+            _ => write!(f, "not run"),
+        }
+    }
+}
+
+#[cfg(test)]
+fn timings(millis: &[u64]) -> Timings {
+    Timings {
+        samples: millis.iter().map(|m| Duration::from_millis(*m)).collect(),
+    }
+}
+
+#[test]
+fn test_stats() {
+    let t = timings(&[4, 1, 7]);
+    assert_eq!(Some(Duration::from_millis(4)), t.mean());
+    assert_eq!(Some(Duration::from_millis(1)), t.min());
+    assert_eq!(Some(Duration::from_millis(7)), t.max());
+    assert_eq!(None, Timings::default().mean());
+}
+
+#[test]
+fn test_display() {
+    assert_eq!("not run", Timings::default().to_string());
+    assert_eq!("2ms", timings(&[2]).to_string());
+    assert_eq!(
+        "mean 2ms, min 1ms, max 3ms over 2 runs",
+        timings(&[1, 3]).to_string()
+    );
+}
+
+#[test]
+fn test_time() {
+    let mut t = Timings::default();
+    assert_eq!(4, t.time(|| 2 + 2));
+    assert_eq!(1, t.samples.len());
+}
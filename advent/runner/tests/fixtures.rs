@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::PathBuf;
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("fixtures")
+}
+
+// check runs one line of answers.txt, saying what went wrong if it didn't give the answer.
+fn check(line: &str) -> Result<(u8, u8), String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (input, part, expected) = match fields.as_slice() {
+        [input, part, expected] => (*input, *part, *expected),
+        _ => return Err(format!("{}: not <input> <part> <answer>", line)),
+    };
+
+    let day: u8 = input
+        .strip_prefix('d')
+        .and_then(|i| i.get(..2))
+        .and_then(|d| d.parse().ok())
+        .ok_or_else(|| format!("{}: can't tell which day this is", input))?;
+    let part: u8 = match part {
+        "1" => 1,
+        "2" => 2,
+        _ => return Err(format!("{}: there is no part {}", input, part)),
+    };
+    let solver =
+        advent::solver(day, part).ok_or_else(|| format!("{}: no solver for day {}", input, day))?;
+
+    let contents =
+        fs::read_to_string(fixtures().join(input)).map_err(|e| format!("{}: {}", input, e))?;
+    match solver(&contents) {
+        Ok(answer) if answer == expected => Ok((day, part)),
+        Ok(answer) => Err(format!(
+            "{} part {}: expected {}, got {}",
+            input, part, expected, answer
+        )),
+        Err(e) => Err(format!(
+            "{} part {}: expected {}, failed with {}",
+            input, part, expected, e
+        )),
+    }
+}
+
+// test_fixtures runs every example in fixtures/answers.txt, and fails listing every answer that
+// didn't match rather than stopping at the first.
+#[test]
+fn test_fixtures() {
+    let answers = fs::read_to_string(fixtures().join("answers.txt")).unwrap();
+    let mut covered = Vec::new();
+    let mut mismatches = Vec::new();
+    for line in answers.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match check(line) {
+            Ok(solved) => covered.push(solved),
+            Err(mismatch) => mismatches.push(mismatch),
+        }
+    }
+
+    for day in 1..=10 {
+        for part in 1..=2 {
+            if !covered.contains(&(day, part)) {
+                mismatches.push(format!("day {} part {} has no fixture", day, part));
+            }
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "{} fixtures failed:\n{}",
+        mismatches.len(),
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn test_no_solver() {
+    assert_eq!(
+        Err("d00-example.txt: no solver for day 0".to_string()),
+        check("d00-example.txt 1 5")
+    );
+    assert_eq!(
+        Err("d11-example.txt: no solver for day 11".to_string()),
+        check("d11-example.txt 2 5")
+    );
+}
@@ -0,0 +1,170 @@
+use crate::policy::{Arrival, Decision, Policy};
+use crate::visitor::Visitor;
+use crate::visitor_list::VisitorList;
+use chrono::{DateTime, Local, Utc};
+
+// Door is the treehouse bouncer. It checks every arrival against the policy and keeps the visitor
+// list up to date with everyone it lets in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Door {
+    pub visitor_list: VisitorList,
+    pub policy: Policy,
+
+    // How many people are inside right now: everyone let in, less everyone who's left.
+    pub inside: usize,
+}
+
+// Admission is the outcome of someone knocking on the door.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Admission {
+    pub name: String,
+    pub decision: Decision,
+    // The visitor's greeting, only set when they were let in.
+    pub greeting: Option<String>,
+    // Whether this arrival added the visitor to the list.
+    pub added: bool,
+}
+
+impl Door {
+    pub fn new(visitor_list: VisitorList, policy: Policy) -> Door {
+        Door {
+            visitor_list,
+            policy,
+            inside: 0,
+        }
+    }
+
+    // needs_age is true if the policy cares about age and we don't know how old name is yet.
+    pub fn needs_age(&self, name: &str) -> bool {
+        let known_age = self.visitor_list.find(name).and_then(|visitor| visitor.age);
+        self.policy.needs_age() && known_age.is_none()
+    }
+
+    // arrive checks name against the policy. If they're let in, their visit is recorded, and
+    // anyone not already on the list gets added to it. A given age replaces whatever was on file.
+    pub fn arrive(&mut self, name: &str, age: Option<u32>, at: DateTime<Local>) -> Admission {
+        let name = name.to_lowercase();
+        let known = self.visitor_list.find(&name);
+        let age = age.or_else(|| known.and_then(|visitor| visitor.age));
+
+        let decision = self.policy.evaluate(&Arrival {
+            name: &name,
+            age,
+            known: known.is_some(),
+            at: at.time(),
+            occupancy: self.inside,
+        });
+
+        if !decision.admitted() {
+            return Admission {
+                name,
+                decision,
+                greeting: None,
+                added: false,
+            };
+        }
+
+        self.inside += 1;
+        let added = known.is_none();
+        if added {
+            self.visitor_list.add(Visitor::new(&name, "New friend"));
+        }
+
+        let visitor = self.visitor_list.find_mut(&name).unwrap();
+        visitor.age = age;
+        visitor.record_visit(at.with_timezone(&Utc));
+
+        Admission {
+            greeting: Some(visitor.greeting.clone()),
+            name,
+            decision,
+            added,
+        }
+    }
+
+    // leave lets someone out, making room for the next arrival. It's false if nobody was inside.
+    pub fn leave(&mut self) -> bool {
+        if self.inside == 0 {
+            return false;
+        }
+        self.inside -= 1;
+        true
+    }
+}
+
+#[test]
+fn test_arrive_adds_new_visitors() {
+    let mut door = Door::new(VisitorList::default_list(), Policy::default());
+
+    let admission = door.arrive("Bert", None, Local::now());
+    assert!(admission.decision.admitted());
+    assert_eq!(Some("Hello Bert, enjoy your treehouse.".to_string()), admission.greeting);
+    assert!(!admission.added);
+
+    let admission = door.arrive("alice", Some(9), Local::now());
+    assert!(admission.added);
+    assert_eq!(Some("New friend".to_string()), admission.greeting);
+    assert_eq!(Some(9), door.visitor_list.find("alice").unwrap().age);
+    assert_eq!(2, door.inside);
+}
+
+#[test]
+fn test_refused_visitors_are_not_recorded() {
+    let policy = Policy::parse(
+        r#"
+[[rules]]
+rule = "members_only"
+"#,
+    )
+    .unwrap();
+    let mut door = Door::new(VisitorList::default_list(), policy);
+
+    let admission = door.arrive("alice", None, Local::now());
+    assert!(!admission.decision.admitted());
+    assert_eq!(None, admission.greeting);
+    assert_eq!(None, door.visitor_list.find("alice"));
+    assert_eq!(0, door.inside);
+}
+
+#[test]
+fn test_leaving_makes_room() {
+    let policy = Policy::parse(
+        r#"
+[[rules]]
+rule = "capacity"
+limit = 1
+"#,
+    )
+    .unwrap();
+    let mut door = Door::new(VisitorList::default_list(), policy);
+
+    assert!(door.arrive("bert", None, Local::now()).decision.admitted());
+    assert!(!door.arrive("alice", None, Local::now()).decision.admitted());
+
+    assert!(door.leave());
+    assert_eq!(0, door.inside);
+    assert!(door.arrive("alice", None, Local::now()).decision.admitted());
+    assert_eq!(1, door.inside);
+
+    assert!(door.leave());
+    assert!(!door.leave());
+    assert_eq!(0, door.inside);
+}
+
+#[test]
+fn test_needs_age_uses_the_list() {
+    let policy = Policy::parse(
+        r#"
+[[rules]]
+rule = "minimum_age"
+age = 8
+"#,
+    )
+    .unwrap();
+    let mut door = Door::new(VisitorList::default_list(), policy);
+    assert!(door.needs_age("bert"));
+
+    door.arrive("bert", Some(10), Local::now());
+    assert!(!door.needs_age("bert"));
+    assert!(door.arrive("bert", None, Local::now()).decision.admitted());
+}
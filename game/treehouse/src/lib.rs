@@ -0,0 +1,4 @@
+pub mod door;
+pub mod policy;
+pub mod visitor;
+pub mod visitor_list;
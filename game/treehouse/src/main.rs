@@ -1,45 +1,101 @@
+use chrono::Local;
+use std::env;
 use std::io::stdin;
+use std::path::{Path, PathBuf};
+use std::process;
+use treehouse::door::Door;
+use treehouse::policy::Policy;
+use treehouse::visitor_list::VisitorList;
 
-struct Visitor {
-    name: String,
-    greeting: String
-}
+// Where the visitor list lives between runs, overridable with TREEHOUSE_VISITORS.
+const DEFAULT_VISITOR_FILE: &str = "visitors.json";
+// Where the door rules live, overridable with TREEHOUSE_POLICY.
+const DEFAULT_POLICY_FILE: &str = "policy.toml";
 
-impl Visitor {
-    fn new(name: &str, greeting: &str) -> Visitor {
-        Visitor{
-            name: name.to_lowercase(),
-            greeting: greeting.to_string()
-        }
-    }
+fn main() {
+    let path = file_from_env("TREEHOUSE_VISITORS", DEFAULT_VISITOR_FILE);
+    let visitor_list = VisitorList::load(&path).unwrap_or_else(|err| {
+        eprintln!("Problem loading visitors: {}", err);
+        process::exit(1);
+    });
+    let policy = Policy::load(&file_from_env("TREEHOUSE_POLICY", DEFAULT_POLICY_FILE))
+        .unwrap_or_else(|err| {
+            eprintln!("Problem loading policy: {}", err);
+            process::exit(1);
+        });
 
-    fn greet_visitor(&self) {
-        println!("{}", self.greeting);
+    let mut door = Door::new(visitor_list, policy);
+    match env::args().nth(1).as_deref() {
+        None | Some("door") => greet_arrivals(&mut door, &path),
+        Some("report") => report(&door.visitor_list),
+        Some(other) => {
+            eprintln!("Unknown command {}, expected door or report", other);
+            process::exit(1);
+        }
     }
 }
 
-fn main() {
-    let visitor_list = [
-        Visitor::new("bert", "Hello Bert, enjoy your treehouse."),
-        Visitor::new("steve", "Hi Steve. Your milk is in the fridge."),
-        Visitor::new("fred", "Wow, who invited Fred?"),
-    ];
-    println!("Hello, what's your name?");
-    let name = what_is_your_name();
+// greet_arrivals asks everyone at the door their name until an empty name is given. The door
+// decides who gets in, and the visitor list is saved after every visit.
+fn greet_arrivals(door: &mut Door, path: &Path) {
+    loop {
+        println!("Hello, what's your name? (Leave empty and press ENTER to quit)");
+        let name = what_is_your_name();
+        if name.is_empty() {
+            break;
+        }
 
-    println!("Hello, {}", name);
-    let res = visitor_list.iter().find(|visitor| visitor.name == &name);
+        println!("Hello, {}", name);
+        let age = if door.needs_age(&name) {
+            how_old_are_you()
+        } else {
+            None
+        };
 
-    match res {
-        None => {
-            println!("You are not on the list, {}", name);
+        let admission = door.arrive(&name, age, Local::now());
+        if !admission.decision.admitted() {
+            let reasons = admission.decision.failures().join(", ");
+            println!("Sorry {}, you can't come in: {}", name, reasons);
+            continue;
         }
-        Some(v) => {
-            visitor.unwrap().greet_visitor();
+
+        println!("The bouncer says {}", admission.decision);
+        if admission.added {
+            println!("You are not on the list, {}. We'll add you this time.", name);
+        }
+        if let Some(greeting) = admission.greeting {
+            println!("{}", greeting);
+        }
+
+        if let Err(err) = door.visitor_list.save(path) {
+            eprintln!("Problem saving visitors: {}", err);
         }
     }
 }
 
+fn report(visitor_list: &VisitorList) {
+    let visited = visitor_list.report();
+    if visited.is_empty() {
+        println!("Nobody has visited the treehouse yet.");
+        return;
+    }
+
+    for visitor in visited {
+        let last_seen = match visitor.last_seen {
+            Some(at) => at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            None => "never".to_string(),
+        };
+        println!("{}: {} visits, last seen {}", visitor.name, visitor.visits, last_seen);
+    }
+}
+
+fn file_from_env(var: &str, default: &str) -> PathBuf {
+    match env::var(var) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => PathBuf::from(default),
+    }
+}
+
 fn what_is_your_name() -> String {
     let mut your_name = String::new();
     stdin()
@@ -49,3 +105,19 @@ fn what_is_your_name() -> String {
         .trim()
         .to_lowercase()
 }
+
+// how_old_are_you keeps asking until it gets a number, or gives up on an empty answer.
+fn how_old_are_you() -> Option<u32> {
+    loop {
+        println!("How old are you?");
+        let answer = what_is_your_name();
+        if answer.is_empty() {
+            return None;
+        }
+
+        match answer.parse() {
+            Ok(age) => return Some(age),
+            Err(_) => println!("{} isn't an age", answer),
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+// Policy is the set of door rules for the treehouse. It's loaded from a TOML file that looks like:
+//
+//   [[rules]]
+//   rule = "minimum_age"
+//   age = 8
+//
+//   [[rules]]
+//   rule = "opening_hours"
+//   from = "15:00"
+//   to = "20:30"
+//
+// Every rule is checked against every arrival, and the arrival only gets in if all of them pass.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct Policy {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum Rule {
+    // Only visitors already on the visitor list get in.
+    MembersOnly,
+    MinimumAge {
+        age: u32,
+    },
+    BannedNames {
+        names: Vec<String>,
+    },
+    // The most visitors allowed in the treehouse at once.
+    Capacity {
+        limit: usize,
+    },
+    // The door is only open between from and to. A window like 22:00 -> 02:00 wraps past midnight.
+    OpeningHours {
+        #[serde(deserialize_with = "deserialize_time")]
+        from: NaiveTime,
+        #[serde(deserialize_with = "deserialize_time")]
+        to: NaiveTime,
+    },
+}
+
+// Arrival is everything the bouncer knows about someone knocking on the door.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arrival<'a> {
+    pub name: &'a str,
+    pub age: Option<u32>,
+    pub known: bool,
+    pub at: NaiveTime,
+    // How many visitors are already inside.
+    pub occupancy: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleOutcome {
+    pub rule: Rule,
+    pub passed: bool,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub outcomes: Vec<RuleOutcome>,
+}
+
+impl Decision {
+    pub fn admitted(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.passed)
+    }
+
+    // failures are the explanations of every rule the arrival broke.
+    pub fn failures(&self) -> Vec<&str> {
+        self.outcomes
+            .iter()
+            .filter(|outcome| !outcome.passed)
+            .map(|outcome| outcome.explanation.as_str())
+            .collect()
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.admitted() {
+            write!(f, "admitted")?;
+        } else {
+            write!(f, "refused")?;
+        }
+
+        for outcome in self.outcomes.iter() {
+            let mark = if outcome.passed { "ok" } else { "FAIL" };
+            write!(f, "\n  [{}] {}: {}", mark, outcome.rule, outcome.explanation)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::MembersOnly => write!(f, "members only"),
+            Rule::MinimumAge { age } => write!(f, "minimum age {}", age),
+            Rule::BannedNames { names } => write!(f, "banned names {}", names.join(", ")),
+            Rule::Capacity { limit } => write!(f, "capacity {}", limit),
+            Rule::OpeningHours { from, to } => {
+                write!(f, "open {} to {}", from.format("%H:%M"), to.format("%H:%M"))
+            }
+        }
+    }
+}
+
+impl Rule {
+    fn check(&self, arrival: &Arrival) -> RuleOutcome {
+        let (passed, explanation) = match self {
+            Rule::MembersOnly => {
+                if arrival.known {
+                    (true, format!("{} is on the list", arrival.name))
+                } else {
+                    (false, format!("{} is not on the list", arrival.name))
+                }
+            }
+            Rule::MinimumAge { age } => match arrival.age {
+                Some(a) if a >= *age => (true, format!("{} is at least {}", a, age)),
+                Some(a) => (false, format!("{} is younger than {}", a, age)),
+                None => (false, "age is unknown".to_string()),
+            },
+            Rule::BannedNames { names } => {
+                if names.iter().any(|n| n.to_lowercase() == arrival.name.to_lowercase()) {
+                    (false, format!("{} is banned", arrival.name))
+                } else {
+                    (true, format!("{} is not banned", arrival.name))
+                }
+            }
+            Rule::Capacity { limit } => {
+                if arrival.occupancy < *limit {
+                    (true, format!("{} of {} inside", arrival.occupancy, limit))
+                } else {
+                    (false, format!("treehouse is full with {} inside", arrival.occupancy))
+                }
+            }
+            Rule::OpeningHours { from, to } => {
+                let at = arrival.at;
+                let open = if from <= to {
+                    at >= *from && at < *to
+                } else {
+                    at >= *from || at < *to
+                };
+
+                if open {
+                    (true, format!("{} is within opening hours", at.format("%H:%M")))
+                } else {
+                    (false, format!("{} is outside opening hours", at.format("%H:%M")))
+                }
+            }
+        };
+
+        RuleOutcome {
+            rule: self.clone(),
+            passed,
+            explanation,
+        }
+    }
+}
+
+impl Policy {
+    // Loads the policy from path. A missing file means there are no rules and everyone gets in.
+    pub fn load(path: &Path) -> Result<Policy, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Policy::default()),
+            Err(e) => return Err(format!("Could not read {}: {}", path.display(), e)),
+        };
+
+        Policy::parse(&contents).map_err(|e| format!("Could not parse {}: {}", path.display(), e))
+    }
+
+    pub fn parse(contents: &str) -> Result<Policy, String> {
+        toml::from_str(contents).map_err(|e| e.to_string())
+    }
+
+    // needs_age is true if the bouncer has to ask arrivals how old they are.
+    pub fn needs_age(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| matches!(rule, Rule::MinimumAge { .. }))
+    }
+
+    pub fn evaluate(&self, arrival: &Arrival) -> Decision {
+        Decision {
+            outcomes: self.rules.iter().map(|rule| rule.check(arrival)).collect(),
+        }
+    }
+}
+
+fn deserialize_time<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&s, "%H:%M").map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+fn arrival<'a>(name: &'a str, age: Option<u32>, at: &str) -> Arrival<'a> {
+    Arrival {
+        name,
+        age,
+        known: true,
+        at: NaiveTime::parse_from_str(at, "%H:%M").unwrap(),
+        occupancy: 0,
+    }
+}
+
+#[test]
+fn test_parse_policy() {
+    let contents = r#"
+[[rules]]
+rule = "members_only"
+
+[[rules]]
+rule = "minimum_age"
+age = 8
+
+[[rules]]
+rule = "banned_names"
+names = ["fred"]
+
+[[rules]]
+rule = "capacity"
+limit = 3
+
+[[rules]]
+rule = "opening_hours"
+from = "15:00"
+to = "20:30"
+"#;
+    let policy = Policy::parse(contents).unwrap();
+    assert_eq!(5, policy.rules.len());
+    assert_eq!(Rule::MinimumAge { age: 8 }, policy.rules[1]);
+    assert_eq!(
+        Rule::OpeningHours {
+            from: NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
+            to: NaiveTime::from_hms_opt(20, 30, 0).unwrap()
+        },
+        policy.rules[4]
+    );
+    assert!(policy.needs_age());
+}
+
+#[test]
+fn test_parse_rejects_bad_time() {
+    let contents = r#"
+[[rules]]
+rule = "opening_hours"
+from = "3pm"
+to = "20:30"
+"#;
+    assert!(Policy::parse(contents).is_err());
+}
+
+#[test]
+fn test_empty_policy_admits_everyone() {
+    let policy = Policy::default();
+    let decision = policy.evaluate(&arrival("bert", None, "03:00"));
+    assert!(decision.admitted());
+    assert!(!policy.needs_age());
+}
+
+#[test]
+fn test_every_failure_is_explained() {
+    let policy = Policy {
+        rules: vec![
+            Rule::MinimumAge { age: 8 },
+            Rule::BannedNames {
+                names: vec!["Fred".to_string()],
+            },
+            Rule::Capacity { limit: 2 },
+        ],
+    };
+
+    let decision = policy.evaluate(&arrival("fred", Some(6), "12:00"));
+    assert!(!decision.admitted());
+    assert_eq!(
+        vec!["6 is younger than 8", "fred is banned"],
+        decision.failures()
+    );
+
+    let mut full = arrival("bert", Some(9), "12:00");
+    full.occupancy = 2;
+    assert_eq!(
+        vec!["treehouse is full with 2 inside"],
+        policy.evaluate(&full).failures()
+    );
+}
+
+#[test]
+fn test_opening_hours_wrap_past_midnight() {
+    let policy = Policy {
+        rules: vec![Rule::OpeningHours {
+            from: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            to: NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+        }],
+    };
+
+    assert!(policy.evaluate(&arrival("bert", None, "23:30")).admitted());
+    assert!(policy.evaluate(&arrival("bert", None, "01:59")).admitted());
+    assert!(!policy.evaluate(&arrival("bert", None, "02:00")).admitted());
+    assert!(!policy.evaluate(&arrival("bert", None, "12:00")).admitted());
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Visitor {
+    pub name: String,
+    pub greeting: String,
+    #[serde(default)]
+    pub age: Option<u32>,
+
+    // How many times this visitor has been let through the door, and when they were last seen.
+    // Older visitor files won't have these, so they default to "never visited".
+    #[serde(default)]
+    pub visits: u32,
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl Visitor {
+    pub fn new(name: &str, greeting: &str) -> Visitor {
+        Visitor {
+            name: name.to_lowercase(),
+            greeting: greeting.to_string(),
+            age: None,
+            visits: 0,
+            last_seen: None,
+        }
+    }
+
+    pub fn greet_visitor(&self) {
+        println!("{}", self.greeting);
+    }
+
+    pub fn record_visit(&mut self, at: DateTime<Utc>) {
+        self.visits += 1;
+        self.last_seen = Some(at);
+    }
+}
+
+#[test]
+fn test_record_visit() {
+    let mut visitor = Visitor::new("Bert", "Hello Bert, enjoy your treehouse.");
+    assert_eq!("bert", visitor.name);
+    assert_eq!(0, visitor.visits);
+
+    let now = Utc::now();
+    visitor.record_visit(now);
+    visitor.record_visit(now);
+    assert_eq!(2, visitor.visits);
+    assert_eq!(Some(now), visitor.last_seen);
+}
@@ -0,0 +1,91 @@
+use crate::visitor::Visitor;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+// VisitorList is everyone the treehouse knows about. It's stored as JSON so that anyone added at
+// the door is still on the list the next time the treehouse opens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisitorList {
+    pub visitors: Vec<Visitor>,
+}
+
+impl VisitorList {
+    // The list the treehouse starts with when there's no saved file yet.
+    pub fn default_list() -> VisitorList {
+        VisitorList {
+            visitors: vec![
+                Visitor::new("bert", "Hello Bert, enjoy your treehouse."),
+                Visitor::new("steve", "Hi Steve. Your milk is in the fridge."),
+                Visitor::new("fred", "Wow, who invited Fred?"),
+            ],
+        }
+    }
+
+    // Loads the list from path, falling back to the default list if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<VisitorList, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(VisitorList::default_list()),
+            Err(e) => return Err(format!("Could not read {}: {}", path.display(), e)),
+        };
+
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Could not parse {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Could not serialize visitors: {}", e))?;
+        fs::write(path, contents).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Visitor> {
+        let name = name.to_lowercase();
+        self.visitors.iter().find(|visitor| visitor.name == name)
+    }
+
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut Visitor> {
+        let name = name.to_lowercase();
+        self.visitors.iter_mut().find(|visitor| visitor.name == name)
+    }
+
+    pub fn add(&mut self, visitor: Visitor) {
+        self.visitors.push(visitor);
+    }
+
+    // report lists everyone who has actually come through the door, most frequent visitors first.
+    pub fn report(&self) -> Vec<&Visitor> {
+        let mut visited: Vec<&Visitor> = self.visitors.iter().filter(|v| v.visits > 0).collect();
+        visited.sort_by(|a, b| b.visits.cmp(&a.visits).then(a.name.cmp(&b.name)));
+        visited
+    }
+}
+
+#[test]
+fn test_find_is_case_insensitive() {
+    let list = VisitorList::default_list();
+    assert_eq!("bert", list.find("Bert").unwrap().name);
+    assert_eq!(None, list.find("alice"));
+}
+
+#[test]
+fn test_save_and_load() {
+    let path = std::env::temp_dir().join(format!("treehouse-{}.json", std::process::id()));
+    let mut list = VisitorList::default_list();
+    list.add(Visitor::new("alice", "New friend"));
+    list.find_mut("alice").unwrap().record_visit(chrono::Utc::now());
+    list.save(&path).unwrap();
+
+    let loaded = VisitorList::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(list, loaded);
+    assert_eq!(vec!["alice"], loaded.report().iter().map(|v| v.name.as_str()).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_load_missing_file() {
+    let path = std::env::temp_dir().join("treehouse-does-not-exist.json");
+    assert_eq!(VisitorList::default_list(), VisitorList::load(&path).unwrap());
+}
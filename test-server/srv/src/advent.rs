@@ -0,0 +1,148 @@
+use crate::errors::ApiError;
+use crate::events::{Event, Events};
+use bytes::Bytes;
+use serde::Serialize;
+use solvers::solver;
+use std::sync::Arc;
+use std::time::Instant;
+use warp::Filter;
+
+#[derive(Debug, Serialize)]
+pub struct Answer {
+    pub day: u8,
+    pub part: u8,
+    // Answers are strings since some of them are too big for a JSON number.
+    pub answer: String,
+}
+
+// routes solves puzzles as a service:
+//   POST /advent/{day}/{part}   the raw puzzle input in, the answer out
+// Every solver run is published to events.
+pub fn routes(
+    events: Arc<Events>,
+    max_puzzle_input: u64,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("advent" / u8 / u8)
+        .and(warp::post())
+        .and(warp::body::content_length_limit(max_puzzle_input))
+        .and(warp::body::bytes())
+        .and(warp::any().map(move || events.clone()))
+        .and_then(solve)
+}
+
+async fn solve(
+    day: u8,
+    part: u8,
+    body: Bytes,
+    events: Arc<Events>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let part_solver = match solver(day, part) {
+        Some(s) => s,
+        None => {
+            let error = ApiError::NotFound(format!("No solver for day {} part {}", day, part));
+            return Err(warp::reject::custom(error));
+        }
+    };
+
+    let input = match String::from_utf8(body.to_vec()) {
+        Ok(i) => i,
+        Err(_) => {
+            let error = ApiError::BadRequest("Puzzle input must be UTF-8 text".to_string());
+            return Err(warp::reject::custom(error));
+        }
+    };
+
+    // The solvers can take a while on big inputs, and a bug in one shouldn't take a server thread
+    // down with it, so they get their own thread away from the server.
+    let start = Instant::now();
+    let result = tokio::task::spawn_blocking(move || part_solver(&input)).await;
+    events.publish(&Event::SolverRun {
+        day,
+        part,
+        solved: matches!(result, Ok(Ok(_))),
+        millis: start.elapsed().as_secs_f64() * 1000.0,
+    });
+    let answer = match result {
+        Ok(Ok(answer)) => answer,
+        Ok(Err(e)) => return Err(warp::reject::custom(ApiError::Unprocessable(e))),
+        Err(_) => {
+            let error = ApiError::Unprocessable(format!(
+                "Day {} part {} could not solve this input",
+                day, part
+            ));
+            return Err(warp::reject::custom(error));
+        }
+    };
+
+    Ok(warp::reply::json(&Answer { day, part, answer }))
+}
+
+#[cfg(test)]
+fn test_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    routes(
+        Events::new(),
+        crate::limits::Limits::default().max_puzzle_input,
+    )
+}
+
+#[tokio::test]
+async fn test_solve() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/advent/1/1")
+        .body("1721\n979\n366\n299\n675\n1456\n")
+        .reply(&test_routes())
+        .await;
+
+    assert_eq!(200, res.status());
+    assert_eq!(r#"{"day":1,"part":1,"answer":"514579"}"#, res.body());
+}
+
+#[tokio::test]
+async fn test_solve_errors() {
+    let routes = test_routes().recover(crate::errors::handle_rejection);
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/advent/12/1")
+        .body("1\n")
+        .reply(&routes)
+        .await;
+    assert_eq!(404, res.status());
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/advent/3/1")
+        .body("..#\n.x.\n")
+        .reply(&routes)
+        .await;
+    assert_eq!(422, res.status());
+    assert_eq!(
+        r#"{"code":422,"error":"line 2, column 2: Not a parsable character, found \"x\""}"#,
+        res.body()
+    );
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/advent/1/2")
+        .body("1\ntwo\n")
+        .reply(&routes)
+        .await;
+    assert_eq!(422, res.status());
+    assert_eq!(
+        r#"{"code":422,"error":"line 2, column 1: Could not parse number, found \"two\""}"#,
+        res.body()
+    );
+}
+
+#[test]
+fn test_every_day_is_routed() {
+    for day in 1..=10 {
+        for part in 1..=2 {
+            assert!(solver(day, part).is_some(), "day {} part {}", day, part);
+        }
+    }
+    assert!(solver(0, 1).is_none());
+    assert!(solver(11, 1).is_none());
+    assert!(solver(1, 3).is_none());
+}
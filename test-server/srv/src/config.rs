@@ -0,0 +1,267 @@
+use crate::limits::{Limits, Rate};
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+
+// Config is everything the server can be told at startup. Each setting can come from a command
+// line flag or an environment variable, and flags win over the environment:
+//
+//   --address <ip>      SRV_ADDRESS          default 127.0.0.1
+//   --port <port>       SRV_PORT             default 8080
+//   --visitors <path>   TREEHOUSE_VISITORS   default visitors.json
+//   --policy <path>     TREEHOUSE_POLICY     default policy.toml
+//   --static <dir>      SRV_STATIC           no static files unless set
+//   --listing <bool>    SRV_LISTING          default false, list directories under --static
+//   --max-body <bytes>  SRV_MAX_BODY         default 16384, the largest JSON body accepted
+//   --max-input <bytes> SRV_MAX_INPUT        default 1048576, the largest puzzle input accepted
+//   --rate <per second> SRV_RATE             requests per second per IP, unlimited unless set
+//   --burst <requests>  SRV_BURST            default 10, requests per IP at once, only with --rate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub address: IpAddr,
+    pub port: u16,
+    pub visitors_path: PathBuf,
+    pub policy_path: PathBuf,
+    pub static_dir: Option<PathBuf>,
+    pub listing: bool,
+    pub limits: Limits,
+}
+
+impl Config {
+    // new builds the config from args (without the program name) and env, which looks up an
+    // environment variable. Passing env in keeps the tests away from the real environment.
+    pub fn new<I, E>(args: I, env: E) -> Result<Config, String>
+    where
+        I: IntoIterator<Item = String>,
+        E: Fn(&str) -> Option<String>,
+    {
+        let mut address = env("SRV_ADDRESS");
+        let mut port = env("SRV_PORT");
+        let mut visitors_path = env("TREEHOUSE_VISITORS");
+        let mut policy_path = env("TREEHOUSE_POLICY");
+        let mut static_dir = env("SRV_STATIC");
+        let mut listing = env("SRV_LISTING");
+        let mut max_body = env("SRV_MAX_BODY");
+        let mut max_input = env("SRV_MAX_INPUT");
+        let mut rate = env("SRV_RATE");
+        let mut burst = env("SRV_BURST");
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            // Both "--port 80" and "--port=80" are accepted.
+            let (flag, inline_value) = match arg.find('=') {
+                Some(i) => (arg[..i].to_string(), Some(arg[i + 1..].to_string())),
+                None => (arg.clone(), None),
+            };
+
+            let slot = match flag.as_str() {
+                "--address" => &mut address,
+                "--port" => &mut port,
+                "--visitors" => &mut visitors_path,
+                "--policy" => &mut policy_path,
+                "--static" => &mut static_dir,
+                "--listing" => &mut listing,
+                "--max-body" => &mut max_body,
+                "--max-input" => &mut max_input,
+                "--rate" => &mut rate,
+                "--burst" => &mut burst,
+                _ => return Err(format!("Unknown argument {}", arg)),
+            };
+
+            let value = match inline_value.or_else(|| args.next()) {
+                Some(v) => v,
+                None => return Err(format!("{} needs a value", flag)),
+            };
+            *slot = Some(value);
+        }
+
+        let address = match address {
+            Some(a) => a
+                .parse()
+                .map_err(|_| format!("{} is not an IP address", a))?,
+            None => IpAddr::from([127, 0, 0, 1]),
+        };
+
+        let port = match port {
+            Some(p) => p.parse().map_err(|_| format!("{} is not a port", p))?,
+            None => 8080,
+        };
+
+        let listing = match listing {
+            Some(l) => l
+                .parse()
+                .map_err(|_| format!("{} is not true or false", l))?,
+            None => false,
+        };
+
+        let mut limits = Limits::default();
+        if let Some(b) = max_body {
+            limits.max_json_body = b.parse().map_err(|_| format!("{} is not a size", b))?;
+        }
+        if let Some(i) = max_input {
+            limits.max_puzzle_input = i.parse().map_err(|_| format!("{} is not a size", i))?;
+        }
+        let burst = match burst {
+            Some(b) => match b.parse() {
+                Ok(n) if n > 0 => Some(n),
+                _ => return Err(format!("{} is not a burst size", b)),
+            },
+            None => None,
+        };
+        match (rate, burst) {
+            (Some(r), burst) => {
+                let per_second: f64 = r.parse().map_err(|_| format!("{} is not a rate", r))?;
+                if !(per_second > 0.0 && per_second.is_finite()) {
+                    return Err(format!("{} is not a rate", r));
+                }
+                limits.rate = Some(Rate {
+                    per_second,
+                    burst: burst.unwrap_or(10),
+                });
+            }
+            (None, Some(_)) => return Err("--burst needs a --rate to limit".to_string()),
+            (None, None) => {}
+        }
+
+        Ok(Config {
+            address,
+            port,
+            visitors_path: PathBuf::from(visitors_path.unwrap_or_else(|| "visitors.json".into())),
+            policy_path: PathBuf::from(policy_path.unwrap_or_else(|| "policy.toml".into())),
+            static_dir: static_dir.map(PathBuf::from),
+            listing,
+            limits,
+        })
+    }
+
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.address, self.port)
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "address={} port={} visitors={} policy={}",
+            self.address,
+            self.port,
+            self.visitors_path.display(),
+            self.policy_path.display()
+        )?;
+        if let Some(dir) = &self.static_dir {
+            write!(f, " static={} listing={}", dir.display(), self.listing)?;
+        }
+        if let Some(rate) = &self.limits.rate {
+            write!(f, " rate={}/s burst={}", rate.per_second, rate.burst)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn test_defaults() {
+    let config = Config::new(args(&[]), |_| None).unwrap();
+    assert_eq!("127.0.0.1:8080", config.socket_addr().to_string());
+    assert_eq!(PathBuf::from("visitors.json"), config.visitors_path);
+    assert_eq!(PathBuf::from("policy.toml"), config.policy_path);
+    assert_eq!(None, config.static_dir);
+    assert!(!config.listing);
+    assert_eq!(Limits::default(), config.limits);
+}
+
+#[test]
+fn test_limits() {
+    let env = |key: &str| match key {
+        "SRV_RATE" => Some("2.5".to_string()),
+        _ => None,
+    };
+    let config = Config::new(args(&["--max-body", "100", "--burst", "4"]), env).unwrap();
+    assert_eq!(100, config.limits.max_json_body);
+    assert_eq!(
+        Limits::default().max_puzzle_input,
+        config.limits.max_puzzle_input
+    );
+    assert_eq!(
+        Some(Rate {
+            per_second: 2.5,
+            burst: 4
+        }),
+        config.limits.rate
+    );
+    assert!(config.to_string().ends_with("rate=2.5/s burst=4"));
+
+    assert_eq!(
+        Err("0 is not a rate".to_string()),
+        Config::new(args(&["--rate", "0"]), |_| None)
+    );
+    assert_eq!(
+        Err("0 is not a burst size".to_string()),
+        Config::new(args(&["--rate", "1", "--burst", "0"]), |_| None)
+    );
+    assert_eq!(
+        Err("abc is not a burst size".to_string()),
+        Config::new(args(&["--burst", "abc"]), |_| None)
+    );
+    assert_eq!(
+        Err("--burst needs a --rate to limit".to_string()),
+        Config::new(args(&["--burst", "4"]), |_| None)
+    );
+    assert_eq!(
+        Err("1MB is not a size".to_string()),
+        Config::new(args(&["--max-input", "1MB"]), |_| None)
+    );
+}
+
+#[test]
+fn test_static_files() {
+    let config = Config::new(args(&["--static", "fixtures", "--listing=true"]), |_| None).unwrap();
+    assert_eq!(Some(PathBuf::from("fixtures")), config.static_dir);
+    assert!(config.listing);
+    assert!(config.to_string().ends_with("static=fixtures listing=true"));
+}
+
+#[test]
+fn test_flags_win_over_env() {
+    let env = |key: &str| match key {
+        "SRV_ADDRESS" => Some("0.0.0.0".to_string()),
+        "SRV_PORT" => Some("9000".to_string()),
+        _ => None,
+    };
+
+    let config = Config::new(args(&[]), env).unwrap();
+    assert_eq!("0.0.0.0:9000", config.socket_addr().to_string());
+
+    let config = Config::new(args(&["--port", "3000", "--visitors=/tmp/v.json"]), env).unwrap();
+    assert_eq!("0.0.0.0:3000", config.socket_addr().to_string());
+    assert_eq!(PathBuf::from("/tmp/v.json"), config.visitors_path);
+}
+
+#[test]
+fn test_bad_arguments() {
+    assert_eq!(
+        Err("Unknown argument --verbose".to_string()),
+        Config::new(args(&["--verbose"]), |_| None)
+    );
+    assert_eq!(
+        Err("--port needs a value".to_string()),
+        Config::new(args(&["--port"]), |_| None)
+    );
+    assert_eq!(
+        Err("eighty is not a port".to_string()),
+        Config::new(args(&["--port", "eighty"]), |_| None)
+    );
+    assert_eq!(
+        Err("localhost is not an IP address".to_string()),
+        Config::new(args(&["--address", "localhost"]), |_| None)
+    );
+    assert_eq!(
+        Err("yes is not true or false".to_string()),
+        Config::new(args(&["--listing", "yes"]), |_| None)
+    );
+}
@@ -0,0 +1,108 @@
+use serde::Serialize;
+use std::convert::Infallible;
+use warp::http::header::{self, HeaderValue};
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+// ApiError is every way one of our handlers can fail. Handlers reject with it, and
+// handle_rejection turns it into a JSON error with the matching status code.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+    Conflict(String),
+    // The request made sense, but we couldn't do anything with what was in it.
+    Unprocessable(String),
+    // Slow down, and try again in this many seconds.
+    TooManyRequests(u64),
+}
+
+impl warp::reject::Reject for ApiError {}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            ApiError::BadRequest(m)
+            | ApiError::NotFound(m)
+            | ApiError::Conflict(m)
+            | ApiError::Unprocessable(m) => m,
+            ApiError::TooManyRequests(_) => "Too many requests",
+        }
+    }
+}
+
+// ErrorResponse is the body of every error the server sends back.
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub code: u16,
+    pub error: String,
+}
+
+pub fn error_reply(status: StatusCode, error: &str) -> warp::reply::WithStatus<warp::reply::Json> {
+    let body = ErrorResponse {
+        code: status.as_u16(),
+        error: error.to_string(),
+    };
+    warp::reply::with_status(warp::reply::json(&body), status)
+}
+
+// handle_rejection is the last stop for every request that no route accepted. Our own ApiErrors
+// keep their status, warp's built in rejections get mapped to the closest status code, and
+// anything else is reported as a 500.
+pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
+    let mut retry_after = None;
+    let (status, message) = if err.is_not_found() {
+        (StatusCode::NOT_FOUND, "Not found".to_string())
+    } else if let Some(e) = err.find::<ApiError>() {
+        if let ApiError::TooManyRequests(seconds) = e {
+            retry_after = Some(*seconds);
+        }
+        (e.status(), e.message().to_string())
+    } else if let Some(e) = err.find::<warp::filters::body::BodyDeserializeError>() {
+        (StatusCode::BAD_REQUEST, e.to_string())
+    } else if err.find::<warp::reject::PayloadTooLarge>().is_some() {
+        (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "Payload too large".to_string(),
+        )
+    } else if err.find::<warp::reject::LengthRequired>().is_some() {
+        (
+            StatusCode::LENGTH_REQUIRED,
+            "Content-Length required".to_string(),
+        )
+    } else if err.find::<warp::reject::UnsupportedMediaType>().is_some() {
+        (
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Unsupported media type".to_string(),
+        )
+    } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
+        (
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Method not allowed".to_string(),
+        )
+    } else {
+        eprintln!("Unhandled rejection: {:?}", err);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal server error".to_string(),
+        )
+    };
+
+    let mut response = error_reply(status, &message).into_response();
+    if let Some(seconds) = retry_after {
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+    }
+    Ok(response)
+}
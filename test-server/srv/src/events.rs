@@ -0,0 +1,164 @@
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::{future, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use warp::ws::{Message, WebSocket, Ws};
+use warp::Filter;
+
+// Event is something that happened in the server that /ws subscribers get told about.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    RequestHandled {
+        method: String,
+        path: String,
+        status: u16,
+        millis: f64,
+    },
+    SolverRun {
+        day: u8,
+        part: u8,
+        solved: bool,
+        millis: f64,
+    },
+}
+
+// Events is the registry of connected /ws clients. Every published event goes out to all of
+// them, and any client whose connection has gone away is dropped from the registry.
+#[derive(Debug, Default)]
+pub struct Events {
+    clients: Mutex<HashMap<usize, UnboundedSender<Message>>>,
+    next_id: AtomicUsize,
+}
+
+impl Events {
+    pub fn new() -> Arc<Events> {
+        Arc::new(Events::default())
+    }
+
+    pub fn publish(&self, event: &Event) {
+        let text = match serde_json::to_string(event) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("Problem serializing event: {}", e);
+                return;
+            }
+        };
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|_, client| client.unbounded_send(Message::text(text.clone())).is_ok());
+    }
+
+    // subscribe registers a new client, which gets every event published from now on.
+    pub fn subscribe(&self) -> (usize, UnboundedReceiver<Message>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::unbounded();
+        self.clients.lock().unwrap().insert(id, tx);
+        (id, rx)
+    }
+
+    pub fn unsubscribe(&self, id: usize) {
+        self.clients.lock().unwrap().remove(&id);
+    }
+
+    #[cfg(test)]
+    pub fn subscribers(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+}
+
+// routes streams events to anyone who asks:
+//   GET /ws   a websocket of JSON events
+pub fn routes(
+    events: Arc<Events>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("ws").and(warp::ws()).map(move |ws: Ws| {
+        let events = events.clone();
+        ws.on_upgrade(move |socket| connected(socket, events))
+    })
+}
+
+async fn connected(socket: WebSocket, events: Arc<Events>) {
+    let (to_client, mut from_client) = socket.split();
+    let (id, outgoing) = events.subscribe();
+
+    let forward = outgoing.map(Ok).forward(to_client);
+    // Clients don't have anything to say, we only listen so we notice when they leave.
+    let listen = async move {
+        while let Some(message) = from_client.next().await {
+            match message {
+                Ok(m) if m.is_close() => break,
+                Ok(_) => continue,
+                Err(_) => break,
+            }
+        }
+    };
+
+    future::select(Box::pin(forward), Box::pin(listen)).await;
+    events.unsubscribe(id);
+}
+
+// wait_for_subscribers gives spawned websocket tasks a chance to catch up.
+#[cfg(test)]
+pub async fn wait_for_subscribers(events: &Events, count: usize) {
+    for _ in 0..1000 {
+        if events.subscribers() == count {
+            return;
+        }
+        tokio::task::yield_now().await;
+    }
+    panic!(
+        "expected {} subscribers, found {}",
+        count,
+        events.subscribers()
+    );
+}
+
+#[tokio::test]
+async fn test_publish_drops_closed_clients() {
+    let events = Events::new();
+    let (_, mut first) = events.subscribe();
+    let (_, second) = events.subscribe();
+    drop(second);
+
+    events.publish(&Event::SolverRun {
+        day: 1,
+        part: 2,
+        solved: true,
+        millis: 1.5,
+    });
+    assert_eq!(1, events.subscribers());
+
+    let message = first.next().await.unwrap();
+    assert_eq!(
+        r#"{"event":"solver_run","day":1,"part":2,"solved":true,"millis":1.5}"#,
+        message.to_str().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_websocket() {
+    let events = Events::new();
+    let mut client = warp::test::ws()
+        .path("/ws")
+        .handshake(routes(events.clone()))
+        .await
+        .expect("handshake");
+    wait_for_subscribers(&events, 1).await;
+
+    events.publish(&Event::RequestHandled {
+        method: "GET".to_string(),
+        path: "/".to_string(),
+        status: 200,
+        millis: 0.25,
+    });
+    let message = client.recv().await.unwrap();
+    assert_eq!(
+        r#"{"event":"request_handled","method":"GET","path":"/","status":200,"millis":0.25}"#,
+        message.to_str().unwrap()
+    );
+
+    drop(client);
+    wait_for_subscribers(&events, 0).await;
+}
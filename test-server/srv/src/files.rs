@@ -0,0 +1,455 @@
+use crate::errors::ApiError;
+use percent_encoding::percent_decode_str;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use warp::http::{header, Response, StatusCode};
+use warp::Filter;
+
+// Files serves everything under root, so the server can stand in as a fixture host. Directories
+// get an HTML listing when listing is on, and are not found otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Files {
+    pub root: PathBuf,
+    pub listing: bool,
+}
+
+impl Files {
+    pub fn new(root: PathBuf, listing: bool) -> Files {
+        Files { root, listing }
+    }
+
+    // resolve turns the URL path under /files into a path under root. Anything that tries to
+    // climb out of root is refused.
+    fn resolve(&self, tail: &str) -> Option<PathBuf> {
+        let decoded = percent_decode_str(tail).decode_utf8().ok()?;
+        let mut path = self.root.clone();
+        for segment in decoded.split('/') {
+            match segment {
+                "" | "." => continue,
+                ".." => return None,
+                s if s.contains('\\') => return None,
+                s => path.push(s),
+            }
+        }
+        Some(path)
+    }
+}
+
+// The parts of a request that change what we send back for a file.
+#[derive(Debug, Default)]
+struct Conditions {
+    if_none_match: Option<String>,
+    range: Option<String>,
+}
+
+// routes serves the static files, if there are any:
+//   GET /files/{path}   a file, or a listing of a directory
+pub fn routes(
+    files: Option<Files>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("files")
+        .and(warp::get().or(warp::head()).unify())
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("if-none-match"))
+        .and(warp::header::optional::<String>("range"))
+        .and_then(move |full: warp::path::FullPath, if_none_match, range| {
+            let files = files.clone();
+            async move {
+                let files = files.ok_or_else(warp::reject::not_found)?;
+                let conditions = Conditions {
+                    if_none_match,
+                    range,
+                };
+                // The file system calls block, so they get their own thread.
+                let full = full.as_str().to_string();
+                tokio::task::spawn_blocking(move || serve(&files, &full, &conditions))
+                    .await
+                    .map_err(|_| warp::reject::not_found())?
+            }
+        })
+}
+
+fn serve(
+    files: &Files,
+    full_path: &str,
+    conditions: &Conditions,
+) -> Result<Response<Vec<u8>>, warp::Rejection> {
+    // Only the one /files the route matched, so /files/files/x is x in a directory called files.
+    let tail = full_path.strip_prefix("/files").unwrap_or(full_path);
+    let not_found = || warp::reject::custom(ApiError::NotFound(format!("No such file {}", tail)));
+
+    let path = files.resolve(tail).ok_or_else(not_found)?;
+    let metadata = fs::metadata(&path).map_err(|_| not_found())?;
+
+    if metadata.is_dir() {
+        if !files.listing {
+            return Err(not_found());
+        }
+        // Links in the listing are relative, so they only work from behind a trailing slash.
+        if !full_path.ends_with('/') {
+            return Ok(Response::builder()
+                .status(StatusCode::MOVED_PERMANENTLY)
+                .header(header::LOCATION, format!("{}/", full_path))
+                .body(Vec::new())
+                .unwrap());
+        }
+        let html = listing(&path, tail).map_err(|_| not_found())?;
+        return Ok(Response::builder()
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .body(html.into_bytes())
+            .unwrap());
+    }
+
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let etag = format!("\"{:x}-{:x}\"", metadata.len(), modified);
+
+    if let Some(if_none_match) = &conditions.if_none_match {
+        if etag_matches(if_none_match, &etag) {
+            return Ok(Response::builder()
+                .status(StatusCode::NOT_MODIFIED)
+                .header(header::ETAG, etag)
+                .body(Vec::new())
+                .unwrap());
+        }
+    }
+
+    let contents = fs::read(&path).map_err(|_| not_found())?;
+    let len = contents.len() as u64;
+    let content_type = mime_guess::from_path(&path).first_or_octet_stream();
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type.as_ref())
+        .header(header::ETAG, etag)
+        .header(header::ACCEPT_RANGES, "bytes");
+
+    let range = match &conditions.range {
+        Some(r) => parse_range(r, len),
+        None => Ok(None),
+    };
+    let response = match range {
+        Ok(Some((start, end))) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, len),
+            )
+            .body(contents[start as usize..=end as usize].to_vec()),
+        Ok(None) => builder.body(contents),
+        Err(()) => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new()),
+    };
+    Ok(response.unwrap())
+}
+
+// etag_matches checks an If-None-Match header, which is either * or a list of ETags.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(|t| t.trim().trim_start_matches("W/"))
+        .any(|t| t == "*" || t == etag)
+}
+
+// parse_range reads a Range header for a file of len bytes, giving back the first and last byte
+// to send. Ok(None) means send the whole file: the header isn't something we understand, or asks
+// for more than one range. Err means none of the range is in the file.
+fn parse_range(range: &str, len: u64) -> Result<Option<(u64, u64)>, ()> {
+    let spec = match range.trim().strip_prefix("bytes=") {
+        Some(s) if !s.contains(',') => s.trim(),
+        _ => return Ok(None),
+    };
+    let dash = match spec.find('-') {
+        Some(i) => i,
+        None => return Ok(None),
+    };
+    let (start, end) = (&spec[..dash], &spec[dash + 1..]);
+
+    let (start, end) = if start.is_empty() {
+        // bytes=-N is the last N bytes.
+        let suffix: u64 = match end.parse() {
+            Ok(n) => n,
+            Err(_) => return Ok(None),
+        };
+        if suffix == 0 {
+            return Err(());
+        }
+        (len.saturating_sub(suffix), len.checked_sub(1).ok_or(())?)
+    } else {
+        let start: u64 = match start.parse() {
+            Ok(n) => n,
+            Err(_) => return Ok(None),
+        };
+        let end = if end.is_empty() {
+            len.saturating_sub(1)
+        } else {
+            match end.parse::<u64>() {
+                Ok(n) => n.min(len.saturating_sub(1)),
+                Err(_) => return Ok(None),
+            }
+        };
+        (start, end)
+    };
+
+    if start >= len || start > end {
+        return Err(());
+    }
+    Ok(Some((start, end)))
+}
+
+// listing is a page linking to everything in dir, directories first.
+fn listing(dir: &Path, url_path: &str) -> std::io::Result<String> {
+    let mut entries = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let is_dir = entry.file_type()?.is_dir();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        entries.push((!is_dir, name));
+    }
+    entries.sort();
+
+    let title = escape_html(&percent_decode_str(url_path).decode_utf8_lossy());
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><title>Index of {0}</title></head>\n<body>\n<h1>Index of {0}</h1>\n<ul>\n",
+        title
+    );
+    if !url_path.trim_matches('/').is_empty() {
+        html.push_str("<li><a href=\"../\">../</a></li>\n");
+    }
+    for (is_file, name) in entries {
+        let name = if is_file { name } else { format!("{}/", name) };
+        let href: String = name
+            .split('/')
+            .map(|s| percent_encoding::utf8_percent_encode(s, HREF).to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        html.push_str(&format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            href,
+            escape_html(&name)
+        ));
+    }
+    html.push_str("</ul>\n</body>\n</html>\n");
+    Ok(html)
+}
+
+// The characters that need escaping in a file name before it can be a relative link.
+const HREF: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`');
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// test_files makes a fresh directory of files to serve, unique to each test.
+#[cfg(test)]
+fn test_files(name: &str, listing: bool) -> Files {
+    let root = std::env::temp_dir().join(format!("srv-files-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("sub")).unwrap();
+    fs::write(root.join("hello.txt"), "Hello, World!").unwrap();
+    fs::write(root.join("page.html"), "<p>hi</p>").unwrap();
+    fs::write(root.join("two words.txt"), "spaced").unwrap();
+    fs::write(root.join("sub").join("data.json"), "{}").unwrap();
+    Files::new(root, listing)
+}
+
+#[cfg(test)]
+fn test_routes(
+    files: Files,
+) -> impl Filter<Extract = impl warp::Reply, Error = std::convert::Infallible> + Clone {
+    routes(Some(files)).recover(crate::errors::handle_rejection)
+}
+
+#[test]
+fn test_resolve() {
+    let files = Files::new(PathBuf::from("/srv"), false);
+    assert_eq!(
+        Some(PathBuf::from("/srv/a/b.txt")),
+        files.resolve("/a/b.txt")
+    );
+    assert_eq!(Some(PathBuf::from("/srv/a b")), files.resolve("/a%20b"));
+    assert_eq!(Some(PathBuf::from("/srv")), files.resolve("/"));
+    assert_eq!(None, files.resolve("/../etc/passwd"));
+    assert_eq!(None, files.resolve("/a/%2e%2e/%2e%2e/etc"));
+}
+
+#[test]
+fn test_parse_range() {
+    assert_eq!(Ok(Some((0, 4))), parse_range("bytes=0-4", 10));
+    assert_eq!(Ok(Some((5, 9))), parse_range("bytes=5-", 10));
+    assert_eq!(Ok(Some((7, 9))), parse_range("bytes=-3", 10));
+    assert_eq!(Ok(Some((0, 9))), parse_range("bytes=-30", 10));
+    assert_eq!(Ok(Some((8, 9))), parse_range("bytes=8-100", 10));
+    assert_eq!(Err(()), parse_range("bytes=10-", 10));
+    assert_eq!(Err(()), parse_range("bytes=5-2", 10));
+    assert_eq!(Ok(None), parse_range("bytes=0-1,4-5", 10));
+    assert_eq!(Ok(None), parse_range("lines=1-2", 10));
+}
+
+#[tokio::test]
+async fn test_serve_file() {
+    let routes = test_routes(test_files("serve", false));
+
+    let res = warp::test::request()
+        .path("/files/hello.txt")
+        .reply(&routes)
+        .await;
+    assert_eq!(200, res.status());
+    assert_eq!("text/plain", res.headers()["content-type"]);
+    assert_eq!("bytes", res.headers()["accept-ranges"]);
+    assert_eq!("Hello, World!", res.body());
+
+    let res = warp::test::request()
+        .path("/files/page.html")
+        .reply(&routes)
+        .await;
+    assert_eq!("text/html", res.headers()["content-type"]);
+
+    let res = warp::test::request()
+        .path("/files/sub/data.json")
+        .reply(&routes)
+        .await;
+    assert_eq!("application/json", res.headers()["content-type"]);
+
+    let res = warp::test::request()
+        .path("/files/two%20words.txt")
+        .reply(&routes)
+        .await;
+    assert_eq!("spaced", res.body());
+
+    let res = warp::test::request()
+        .path("/files/missing.txt")
+        .reply(&routes)
+        .await;
+    assert_eq!(404, res.status());
+
+    let res = warp::test::request()
+        .path("/files/../Cargo.toml")
+        .reply(&routes)
+        .await;
+    assert_eq!(404, res.status());
+
+    let res = warp::test::request()
+        .path("/files/files/hello.txt")
+        .reply(&routes)
+        .await;
+    assert_eq!(404, res.status());
+}
+
+#[tokio::test]
+async fn test_etag() {
+    let routes = test_routes(test_files("etag", false));
+
+    let res = warp::test::request()
+        .path("/files/hello.txt")
+        .reply(&routes)
+        .await;
+    let etag = res.headers()["etag"].to_str().unwrap().to_string();
+
+    let res = warp::test::request()
+        .path("/files/hello.txt")
+        .header("if-none-match", &etag)
+        .reply(&routes)
+        .await;
+    assert_eq!(304, res.status());
+    assert_eq!(etag, res.headers()["etag"]);
+    assert!(res.body().is_empty());
+
+    let res = warp::test::request()
+        .path("/files/hello.txt")
+        .header("if-none-match", "\"something-else\"")
+        .reply(&routes)
+        .await;
+    assert_eq!(200, res.status());
+}
+
+#[tokio::test]
+async fn test_range() {
+    let routes = test_routes(test_files("range", false));
+
+    let res = warp::test::request()
+        .path("/files/hello.txt")
+        .header("range", "bytes=0-4")
+        .reply(&routes)
+        .await;
+    assert_eq!(206, res.status());
+    assert_eq!("bytes 0-4/13", res.headers()["content-range"]);
+    assert_eq!("Hello", res.body());
+
+    let res = warp::test::request()
+        .path("/files/hello.txt")
+        .header("range", "bytes=-6")
+        .reply(&routes)
+        .await;
+    assert_eq!(206, res.status());
+    assert_eq!("World!", res.body());
+
+    let res = warp::test::request()
+        .path("/files/hello.txt")
+        .header("range", "bytes=20-")
+        .reply(&routes)
+        .await;
+    assert_eq!(416, res.status());
+    assert_eq!("bytes */13", res.headers()["content-range"]);
+}
+
+#[tokio::test]
+async fn test_directory_listing() {
+    let res = warp::test::request()
+        .path("/files/")
+        .reply(&test_routes(test_files("unlisted", false)))
+        .await;
+    assert_eq!(404, res.status());
+
+    let routes = test_routes(test_files("listed", true));
+    let res = warp::test::request().path("/files/").reply(&routes).await;
+    assert_eq!(200, res.status());
+    assert_eq!("text/html; charset=utf-8", res.headers()["content-type"]);
+    let body = String::from_utf8(res.body().to_vec()).unwrap();
+    assert!(body.contains(r#"<a href="sub/">sub/</a>"#));
+    assert!(body.contains(r#"<a href="hello.txt">hello.txt</a>"#));
+    assert!(body.contains(r#"<a href="two%20words.txt">two words.txt</a>"#));
+    assert!(!body.contains("../"));
+    // Directories come before files.
+    assert!(body.find("sub/").unwrap() < body.find("hello.txt").unwrap());
+
+    let res = warp::test::request()
+        .path("/files/sub")
+        .reply(&routes)
+        .await;
+    assert_eq!(301, res.status());
+    assert_eq!("/files/sub/", res.headers()["location"]);
+
+    let res = warp::test::request()
+        .path("/files/sub/")
+        .reply(&routes)
+        .await;
+    let body = String::from_utf8(res.body().to_vec()).unwrap();
+    assert!(body.contains(r#"<a href="../">../</a>"#));
+    assert!(body.contains(r#"<a href="data.json">data.json</a>"#));
+}
+
+#[tokio::test]
+async fn test_no_files_configured() {
+    let res = warp::test::request()
+        .path("/files/hello.txt")
+        .reply(&routes(None).recover(crate::errors::handle_rejection))
+        .await;
+    assert_eq!(404, res.status());
+}
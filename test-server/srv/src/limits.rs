@@ -0,0 +1,320 @@
+use crate::errors::ApiError;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use warp::Filter;
+
+// Once we're tracking this many clients, the ones whose buckets have filled back up get forgotten,
+// and if that isn't enough, so does whoever we heard from longest ago.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+// Nobody is told to wait longer than this, however slow the rate is.
+const MAX_WAIT: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Limits is how much any one client is allowed to send us.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+    // The largest JSON body any route will accept.
+    pub max_json_body: u64,
+    // The largest puzzle input /advent will accept.
+    pub max_puzzle_input: u64,
+    // How fast each IP address can make requests, or None for as fast as they like.
+    pub rate: Option<Rate>,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_json_body: 16 * 1024,
+            // Puzzle inputs are a few kilobytes, so anything past this isn't a puzzle input.
+            max_puzzle_input: 1024 * 1024,
+            rate: None,
+        }
+    }
+}
+
+// Rate is a token bucket: each client starts with burst requests, and gets per_second more back
+// every second, up to burst again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rate {
+    pub per_second: f64,
+    pub burst: u32,
+}
+
+// Clock is where the rate limiter gets the time from, so tests can move it along by hand.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+pub struct RateLimiter {
+    rate: Rate,
+    clock: Arc<dyn Clock>,
+    // Requests without a remote address, like the ones in tests, share the None bucket.
+    buckets: Mutex<HashMap<Option<IpAddr>, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(rate: Rate) -> Arc<RateLimiter> {
+        RateLimiter::with_clock(rate, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(rate: Rate, clock: Arc<dyn Clock>) -> Arc<RateLimiter> {
+        Arc::new(RateLimiter {
+            rate,
+            clock,
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // check takes a token from ip's bucket, or says how long until there will be one.
+    pub fn check(&self, ip: Option<IpAddr>) -> Result<(), Duration> {
+        let now = self.clock.now();
+        let burst = f64::from(self.rate.burst);
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&ip) {
+            let rate = &self.rate;
+            buckets.retain(|_, b| refill(b, rate, now) < burst);
+            if buckets.len() >= MAX_TRACKED_CLIENTS {
+                let stalest = buckets
+                    .iter()
+                    .min_by_key(|(_, b)| b.updated)
+                    .map(|(ip, _)| *ip);
+                if let Some(stalest) = stalest {
+                    buckets.remove(&stalest);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        bucket.tokens = refill(bucket, &self.rate, now);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            let wait =
+                Duration::try_from_secs_f64(missing / self.rate.per_second).unwrap_or(MAX_WAIT);
+            Err(wait.min(MAX_WAIT))
+        }
+    }
+}
+
+// refill is how many tokens bucket has at now.
+fn refill(bucket: &Bucket, rate: &Rate, now: Instant) -> f64 {
+    let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+    (bucket.tokens + elapsed * rate.per_second).min(f64::from(rate.burst))
+}
+
+// rate_limit lets a request through if its IP address has a token to spend, and rejects it with
+// ApiError::TooManyRequests otherwise. With no limiter everything goes through.
+pub fn rate_limit(
+    limiter: Option<Arc<RateLimiter>>,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |addr: Option<SocketAddr>| {
+            let limiter = limiter.clone();
+            async move {
+                let limiter = match limiter {
+                    Some(l) => l,
+                    None => return Ok(()),
+                };
+                limiter.check(addr.map(|a| a.ip())).map_err(|wait| {
+                    // Retry-After is in whole seconds, so round up.
+                    let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+                    warp::reject::custom(ApiError::TooManyRequests(seconds))
+                })
+            }
+        })
+        .untuple_one()
+}
+
+#[cfg(test)]
+pub struct MockClock {
+    now: Mutex<Instant>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub fn new() -> Arc<MockClock> {
+        Arc::new(MockClock {
+            now: Mutex::new(Instant::now()),
+        })
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+fn ip(last: u8) -> Option<IpAddr> {
+    Some(IpAddr::from([10, 0, 0, last]))
+}
+
+#[test]
+fn test_token_bucket() {
+    let clock = MockClock::new();
+    let rate = Rate {
+        per_second: 2.0,
+        burst: 3,
+    };
+    let limiter = RateLimiter::with_clock(rate, clock.clone());
+
+    assert_eq!(Ok(()), limiter.check(ip(1)));
+    assert_eq!(Ok(()), limiter.check(ip(1)));
+    assert_eq!(Ok(()), limiter.check(ip(1)));
+    assert_eq!(Err(Duration::from_millis(500)), limiter.check(ip(1)));
+
+    // Everyone gets their own bucket.
+    assert_eq!(Ok(()), limiter.check(ip(2)));
+
+    clock.advance(Duration::from_millis(250));
+    assert_eq!(Err(Duration::from_millis(250)), limiter.check(ip(1)));
+    clock.advance(Duration::from_millis(250));
+    assert_eq!(Ok(()), limiter.check(ip(1)));
+    assert!(limiter.check(ip(1)).is_err());
+
+    // A long wait only refills up to the burst.
+    clock.advance(Duration::from_secs(60));
+    for _ in 0..3 {
+        assert_eq!(Ok(()), limiter.check(ip(1)));
+    }
+    assert!(limiter.check(ip(1)).is_err());
+}
+
+#[test]
+fn test_tiny_rate() {
+    let rate = Rate {
+        per_second: 1e-20,
+        burst: 1,
+    };
+    let limiter = RateLimiter::with_clock(rate, MockClock::new());
+    assert_eq!(Ok(()), limiter.check(ip(1)));
+    assert_eq!(Err(MAX_WAIT), limiter.check(ip(1)));
+}
+
+#[test]
+fn test_forgets_idle_clients() {
+    let clock = MockClock::new();
+    let rate = Rate {
+        per_second: 1.0,
+        burst: 1,
+    };
+    let limiter = RateLimiter::with_clock(rate, clock.clone());
+    for i in 0..MAX_TRACKED_CLIENTS {
+        limiter
+            .check(Some(IpAddr::from([
+                10,
+                1,
+                (i / 256) as u8,
+                (i % 256) as u8,
+            ])))
+            .unwrap();
+    }
+
+    clock.advance(Duration::from_secs(1));
+    limiter.check(ip(1)).unwrap();
+    assert_eq!(1, limiter.buckets.lock().unwrap().len());
+}
+
+#[test]
+fn test_forgets_stalest_client() {
+    let clock = MockClock::new();
+    let rate = Rate {
+        per_second: 0.001,
+        burst: 1,
+    };
+    let limiter = RateLimiter::with_clock(rate, clock.clone());
+    let client = |i: usize| Some(IpAddr::from([10, 1, (i / 256) as u8, (i % 256) as u8]));
+    for i in 0..MAX_TRACKED_CLIENTS {
+        limiter.check(client(i)).unwrap();
+        clock.advance(Duration::from_millis(1));
+    }
+
+    // Nobody's bucket has filled back up, so the first client makes way for the new one.
+    limiter.check(ip(1)).unwrap();
+    let buckets = limiter.buckets.lock().unwrap();
+    assert_eq!(MAX_TRACKED_CLIENTS, buckets.len());
+    assert!(!buckets.contains_key(&client(0)));
+    assert!(buckets.contains_key(&client(1)));
+    assert!(buckets.contains_key(&ip(1)));
+}
+
+#[tokio::test]
+async fn test_rate_limit_filter() {
+    let clock = MockClock::new();
+    let rate = Rate {
+        per_second: 0.5,
+        burst: 1,
+    };
+    let limiter = RateLimiter::with_clock(rate, clock.clone());
+    let routes = rate_limit(Some(limiter))
+        .map(|| "ok")
+        .recover(crate::errors::handle_rejection);
+    let client: SocketAddr = "10.0.0.1:5000".parse().unwrap();
+
+    let res = warp::test::request()
+        .remote_addr(client)
+        .reply(&routes)
+        .await;
+    assert_eq!(200, res.status());
+
+    let res = warp::test::request()
+        .remote_addr(client)
+        .reply(&routes)
+        .await;
+    assert_eq!(429, res.status());
+    assert_eq!("2", res.headers()["retry-after"]);
+    assert_eq!(r#"{"code":429,"error":"Too many requests"}"#, res.body());
+
+    clock.advance(Duration::from_millis(1500));
+    let res = warp::test::request()
+        .remote_addr(client)
+        .reply(&routes)
+        .await;
+    assert_eq!("1", res.headers()["retry-after"]);
+
+    clock.advance(Duration::from_millis(500));
+    let res = warp::test::request()
+        .remote_addr(client)
+        .reply(&routes)
+        .await;
+    assert_eq!(200, res.status());
+}
+
+#[tokio::test]
+async fn test_no_limiter() {
+    let routes = rate_limit(None).map(|| "ok");
+    for _ in 0..100 {
+        let res = warp::test::request().reply(&routes).await;
+        assert_eq!(200, res.status());
+    }
+}
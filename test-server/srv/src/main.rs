@@ -1,10 +1,65 @@
 #![deny(warnings)]
-use warp::Filter;
+mod advent;
+mod config;
+mod errors;
+mod events;
+mod files;
+mod limits;
+mod metrics;
+mod routes;
+mod visitors;
+
+use config::Config;
+use files::Files;
+use routes::State;
+use std::env;
+use std::process;
+use tokio::signal::unix::{signal, SignalKind};
+use treehouse::door::Door;
+use treehouse::policy::Policy;
+use treehouse::visitor_list::VisitorList;
+use visitors::Treehouse;
 
 #[tokio::main]
 async fn main() {
-    // Match any request and return hello world!
-    let routes = warp::any().map(|| "Hello, World!");
+    let config = Config::new(env::args().skip(1), |key| env::var(key).ok()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}", err);
+        process::exit(1);
+    });
+
+    let visitor_list = VisitorList::load(&config.visitors_path).unwrap_or_else(|err| {
+        eprintln!("Problem loading visitors: {}", err);
+        process::exit(1);
+    });
+    let policy = Policy::load(&config.policy_path).unwrap_or_else(|err| {
+        eprintln!("Problem loading policy: {}", err);
+        process::exit(1);
+    });
+    let treehouse = Treehouse::new(
+        Door::new(visitor_list, policy),
+        Some(config.visitors_path.clone()),
+    );
+
+    let mut state = State::new(treehouse).with_limits(config.limits.clone());
+    state.files = config
+        .static_dir
+        .clone()
+        .map(|dir| Files::new(dir, config.listing));
+
+    let (addr, server) = warp::serve(routes::routes(state))
+        .bind_with_graceful_shutdown(config.socket_addr(), shutdown_signal());
+
+    println!("Listening on {} with {}", addr, config);
+    server.await;
+    println!("Server stopped");
+}
 
-    warp::serve(routes).run(([127, 0, 0, 1], 8080)).await;
-}
\ No newline at end of file
+// shutdown_signal finishes on the first SIGINT or SIGTERM. The server stops accepting new
+// connections at that point, but lets the requests already in flight finish.
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => println!("Received SIGINT, shutting down"),
+        _ = terminate.recv() => println!("Received SIGTERM, shutting down"),
+    }
+}
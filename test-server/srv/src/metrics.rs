@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use warp::http::{Method, StatusCode};
+use warp::Filter;
+
+// The upper bounds, in seconds, of the request latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Histogram {
+    // counts[i] is how many observations were at most LATENCY_BUCKETS[i].
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new() -> Histogram {
+        Histogram {
+            counts: vec![0; LATENCY_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, seconds: f64) {
+        for (i, bound) in LATENCY_BUCKETS.iter().enumerate() {
+            if seconds <= *bound {
+                self.counts[i] += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+// Metrics counts every request the server handles, and how long they took, keyed by route rather
+// than the full path so that /advent/1/1 and /advent/7/2 don't each get their own series.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    inner: Mutex<MetricsInner>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsInner {
+    // (method, route, status) -> count
+    requests: BTreeMap<(String, String, u16), u64>,
+    latencies: BTreeMap<String, Histogram>,
+}
+
+impl Metrics {
+    pub fn new() -> Arc<Metrics> {
+        Arc::new(Metrics::default())
+    }
+
+    pub fn record(&self, method: &Method, path: &str, status: StatusCode, elapsed: Duration) {
+        let route = route_of(path);
+        let mut inner = self.inner.lock().unwrap();
+        *inner
+            .requests
+            .entry((method.to_string(), route.clone(), status.as_u16()))
+            .or_insert(0) += 1;
+        inner
+            .latencies
+            .entry(route)
+            .or_insert_with(Histogram::new)
+            .observe(elapsed.as_secs_f64());
+    }
+
+    // render writes every metric out in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let inner = self.inner.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# HELP http_requests_total Requests handled, by method, route and status.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        for ((method, route, status), count) in inner.requests.iter() {
+            writeln!(
+                out,
+                "http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                method, route, status, count
+            )
+            .unwrap();
+        }
+
+        out.push_str("# HELP http_request_duration_seconds How long requests took, by route.\n");
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        for (route, histogram) in inner.latencies.iter() {
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.counts.iter()) {
+                writeln!(
+                    out,
+                    "http_request_duration_seconds_bucket{{route=\"{}\",le=\"{}\"}} {}",
+                    route, bound, count
+                )
+                .unwrap();
+            }
+            writeln!(
+                out,
+                "http_request_duration_seconds_bucket{{route=\"{}\",le=\"+Inf\"}} {}",
+                route, histogram.count
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "http_request_duration_seconds_sum{{route=\"{}\"}} {}",
+                route, histogram.sum
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "http_request_duration_seconds_count{{route=\"{}\"}} {}",
+                route, histogram.count
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
+
+// ROUTES are the first path segments the server has routes for.
+const ROUTES: &[&str] = &[
+    "advent", "arrivals", "files", "healthz", "metrics", "visitors", "ws",
+];
+
+// route_of keeps only the first segment of path, which is enough to tell our routes apart. Paths
+// we don't serve all count as "other", so scanners can't add a series for every path they try.
+fn route_of(path: &str) -> String {
+    match path.trim_start_matches('/').split('/').next() {
+        Some("") | None => "/".to_string(),
+        Some(segment) if ROUTES.contains(&segment) => format!("/{}", segment),
+        Some(_) => "other".to_string(),
+    }
+}
+
+// routes serves the metrics and the health check:
+//   GET /metrics   request counts and latencies for Prometheus
+//   GET /healthz   a 200 for as long as the server is up
+pub fn routes(
+    metrics: Arc<Metrics>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let scrape = warp::path!("metrics").and(warp::get()).map(move || {
+        warp::reply::with_header(
+            metrics.render(),
+            "content-type",
+            "text/plain; version=0.0.4",
+        )
+    });
+
+    let health = warp::path!("healthz")
+        .and(warp::get())
+        .map(|| warp::reply::json(&serde_json::json!({"status": "ok"})));
+
+    scrape.or(health)
+}
+
+#[test]
+fn test_route_of() {
+    assert_eq!("/", route_of("/"));
+    assert_eq!("/visitors", route_of("/visitors"));
+    assert_eq!("/advent", route_of("/advent/7/1"));
+    assert_eq!("other", route_of("/nowhere"));
+    assert_eq!("other", route_of("/wp-admin/setup.php"));
+}
+
+#[test]
+fn test_render() {
+    let metrics = Metrics::new();
+    let millis = Duration::from_millis(2);
+    metrics.record(&Method::GET, "/visitors", StatusCode::OK, millis);
+    metrics.record(&Method::GET, "/visitors", StatusCode::OK, millis);
+    metrics.record(&Method::POST, "/advent/1/1", StatusCode::NOT_FOUND, millis);
+
+    let rendered = metrics.render();
+    assert!(rendered
+        .contains("http_requests_total{method=\"GET\",route=\"/visitors\",status=\"200\"} 2\n"));
+    assert!(rendered
+        .contains("http_requests_total{method=\"POST\",route=\"/advent\",status=\"404\"} 1\n"));
+    assert!(rendered
+        .contains("http_request_duration_seconds_bucket{route=\"/visitors\",le=\"0.001\"} 0\n"));
+    assert!(rendered
+        .contains("http_request_duration_seconds_bucket{route=\"/visitors\",le=\"0.005\"} 2\n"));
+    assert!(rendered
+        .contains("http_request_duration_seconds_bucket{route=\"/visitors\",le=\"+Inf\"} 2\n"));
+    assert!(rendered.contains("http_request_duration_seconds_count{route=\"/advent\"} 1\n"));
+}
@@ -0,0 +1,506 @@
+use crate::advent;
+use crate::errors::handle_rejection;
+use crate::events::{self, Event, Events};
+use crate::files::{self, Files};
+use crate::limits::{self, Limits, RateLimiter};
+use crate::metrics::{self, Metrics};
+use crate::visitors::{self, SharedTreehouse};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use warp::Filter;
+
+#[derive(Debug, Serialize)]
+pub struct Message {
+    pub message: String,
+}
+
+// State is everything the routes share between requests.
+#[derive(Clone)]
+pub struct State {
+    pub treehouse: SharedTreehouse,
+    pub metrics: Arc<Metrics>,
+    pub events: Arc<Events>,
+    // Where /files serves from, if anywhere.
+    pub files: Option<Files>,
+    pub limits: Limits,
+    // Built from limits.rate, shared so every request draws from the same buckets.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl State {
+    pub fn new(treehouse: SharedTreehouse) -> State {
+        State {
+            treehouse,
+            metrics: Metrics::new(),
+            events: Events::new(),
+            files: None,
+            limits: Limits::default(),
+            rate_limiter: None,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> State {
+        self.rate_limiter = limits.rate.clone().map(RateLimiter::new);
+        self.limits = limits;
+        self
+    }
+}
+
+// routes is every route the server knows about:
+//   GET  /           hello world
+//   GET  /healthz    health check
+//   GET  /metrics    request metrics for Prometheus
+//   GET  /visitors   the treehouse visitor list
+//   POST /visitors   add someone to the visitor list
+//   POST /arrivals   knock on the treehouse door
+//   POST /advent/{day}/{part}   solve an advent of code puzzle
+//   GET  /files/{path}          a static file, when there's a directory to serve
+//   GET  /ws         a websocket of server events
+// Anything that doesn't match, or fails along the way, comes back as a JSON error. Every request
+// is rate limited first, then logged, counted and published as an event, errors included.
+pub fn routes(state: State) -> impl Filter<Extract = impl warp::Reply, Error = Infallible> + Clone {
+    let routes = index()
+        .or(metrics::routes(state.metrics.clone()))
+        .or(visitors::routes(
+            state.treehouse,
+            state.limits.max_json_body,
+        ))
+        .or(advent::routes(
+            state.events.clone(),
+            state.limits.max_puzzle_input,
+        ))
+        .or(files::routes(state.files))
+        .or(events::routes(state.events.clone()));
+
+    limits::rate_limit(state.rate_limiter)
+        .and(routes)
+        .recover(handle_rejection)
+        .with(log(state.metrics, state.events))
+}
+
+// log writes an access log line for every request, and records it in metrics and events.
+fn log(
+    metrics: Arc<Metrics>,
+    events: Arc<Events>,
+) -> warp::log::Log<impl Fn(warp::log::Info) + Clone> {
+    warp::log::custom(move |info| {
+        println!(
+            "{} {} {} {:?}",
+            info.method(),
+            info.path(),
+            info.status().as_u16(),
+            info.elapsed()
+        );
+        metrics.record(info.method(), info.path(), info.status(), info.elapsed());
+        events.publish(&Event::RequestHandled {
+            method: info.method().to_string(),
+            path: info.path().to_string(),
+            status: info.status().as_u16(),
+            millis: info.elapsed().as_secs_f64() * 1000.0,
+        });
+    })
+}
+
+fn index() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path::end().and(warp::get()).map(|| {
+        warp::reply::json(&Message {
+            message: "Hello, World!".to_string(),
+        })
+    })
+}
+
+// json_body extracts a JSON request body, refusing anything bigger than max_len bytes.
+pub fn json_body<T: DeserializeOwned + Send>(
+    max_len: u64,
+) -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(max_len).and(warp::body::json())
+}
+
+#[cfg(test)]
+fn test_state() -> State {
+    use crate::visitors::Treehouse;
+    use treehouse::door::Door;
+    use treehouse::policy::Policy;
+    use treehouse::visitor_list::VisitorList;
+
+    let door = Door::new(VisitorList::default_list(), Policy::default());
+    State::new(Treehouse::new(door, None))
+}
+
+#[cfg(test)]
+fn test_routes() -> impl Filter<Extract = impl warp::Reply, Error = Infallible> + Clone {
+    routes(test_state())
+}
+
+#[tokio::test]
+async fn test_index() {
+    let res = warp::test::request()
+        .method("GET")
+        .path("/")
+        .reply(&test_routes())
+        .await;
+
+    assert_eq!(200, res.status());
+    assert_eq!("application/json", res.headers()["content-type"]);
+    assert_eq!(r#"{"message":"Hello, World!"}"#, res.body());
+}
+
+#[tokio::test]
+async fn test_unknown_route() {
+    let res = warp::test::request()
+        .method("GET")
+        .path("/nowhere")
+        .reply(&test_routes())
+        .await;
+
+    assert_eq!(404, res.status());
+    assert_eq!(r#"{"code":404,"error":"Not found"}"#, res.body());
+}
+
+#[tokio::test]
+async fn test_wrong_method() {
+    let res = warp::test::request()
+        .method("DELETE")
+        .path("/visitors")
+        .reply(&test_routes())
+        .await;
+
+    assert_eq!(405, res.status());
+    assert_eq!(r#"{"code":405,"error":"Method not allowed"}"#, res.body());
+}
+
+#[tokio::test]
+async fn test_bad_json() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/arrivals")
+        .header("content-type", "application/json")
+        .body("{\"nom\": 3}")
+        .reply(&test_routes())
+        .await;
+
+    assert_eq!(400, res.status());
+    let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(400, body["code"]);
+}
+
+#[tokio::test]
+async fn test_body_too_large() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/arrivals")
+        .header("content-type", "application/json")
+        .body(vec![b' '; Limits::default().max_json_body as usize + 1])
+        .reply(&test_routes())
+        .await;
+
+    assert_eq!(413, res.status());
+}
+
+#[tokio::test]
+async fn test_length_required() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/arrivals")
+        .header("content-type", "application/json")
+        .reply(&test_routes())
+        .await;
+
+    assert_eq!(411, res.status());
+    assert_eq!(
+        r#"{"code":411,"error":"Content-Length required"}"#,
+        res.body()
+    );
+}
+
+#[tokio::test]
+async fn test_healthz() {
+    let res = warp::test::request()
+        .method("GET")
+        .path("/healthz")
+        .reply(&test_routes())
+        .await;
+
+    assert_eq!(200, res.status());
+    assert_eq!(r#"{"status":"ok"}"#, res.body());
+}
+
+#[tokio::test]
+async fn test_metrics_count_requests() {
+    let routes = routes(test_state());
+    for path in &["/", "/", "/nowhere"] {
+        warp::test::request().path(path).reply(&routes).await;
+    }
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/metrics")
+        .reply(&routes)
+        .await;
+    assert_eq!(200, res.status());
+    assert_eq!("text/plain; version=0.0.4", res.headers()["content-type"]);
+
+    let body = String::from_utf8(res.body().to_vec()).unwrap();
+    assert!(body.contains(r#"http_requests_total{method="GET",route="/",status="200"} 2"#));
+    assert!(body.contains(r#"http_requests_total{method="GET",route="other",status="404"} 1"#));
+    assert!(body.contains(r#"http_request_duration_seconds_count{route="/"} 2"#));
+}
+
+#[tokio::test]
+async fn test_events() {
+    let state = test_state();
+    let routes = routes(state.clone());
+    let mut client = warp::test::ws()
+        .path("/ws")
+        .handshake(routes.clone())
+        .await
+        .expect("handshake");
+    events::wait_for_subscribers(&state.events, 1).await;
+
+    warp::test::request()
+        .method("POST")
+        .path("/advent/1/1")
+        .body("1721\n979\n366\n299\n675\n1456\n")
+        .reply(&routes)
+        .await;
+
+    let solved: serde_json::Value =
+        serde_json::from_str(client.recv().await.unwrap().to_str().unwrap()).unwrap();
+    assert_eq!("solver_run", solved["event"]);
+    assert_eq!(1, solved["day"]);
+    assert_eq!(true, solved["solved"]);
+
+    let handled: serde_json::Value =
+        serde_json::from_str(client.recv().await.unwrap().to_str().unwrap()).unwrap();
+    assert_eq!("request_handled", handled["event"]);
+    assert_eq!("/advent/1/1", handled["path"]);
+    assert_eq!(200, handled["status"]);
+}
+
+#[tokio::test]
+async fn test_configured_limits() {
+    let limits = Limits {
+        max_json_body: 10,
+        max_puzzle_input: 5,
+        rate: Some(limits::Rate {
+            per_second: 1.0,
+            burst: 3,
+        }),
+    };
+    let routes = routes(test_state().with_limits(limits));
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/arrivals")
+        .json(&serde_json::json!({"name": "steve"}))
+        .reply(&routes)
+        .await;
+    assert_eq!(413, res.status());
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/advent/1/1")
+        .body("1721\n979\n")
+        .reply(&routes)
+        .await;
+    assert_eq!(413, res.status());
+
+    // The burst is used up, and rate limited requests are still logged.
+    let res = warp::test::request().path("/").reply(&routes).await;
+    assert_eq!(200, res.status());
+    let res = warp::test::request().path("/metrics").reply(&routes).await;
+    assert_eq!(429, res.status());
+    assert_eq!("1", res.headers()["retry-after"]);
+}
+
+// A request to every route, with the status, content type and body it answered with before the
+// move to tokio 1 and warp 0.3. These were captured by sending the same requests to the server as
+// it was just before that move, which was meant to change none of them, so any change here is a
+// change in behaviour. They run in order against one server too, so /metrics comes first, before
+// anything has been counted, and the visitor list is fetched before anyone arrives.
+#[cfg(test)]
+const ROUTE_TABLE: [(&str, &str, &str, u16, &str, &str); 13] = [
+    (
+        "GET",
+        "/metrics",
+        "",
+        200,
+        "text/plain; version=0.0.4",
+        "\
+# HELP http_requests_total Requests handled, by method, route and status.
+# TYPE http_requests_total counter
+# HELP http_request_duration_seconds How long requests took, by route.
+# TYPE http_request_duration_seconds histogram
+",
+    ),
+    (
+        "GET",
+        "/",
+        "",
+        200,
+        "application/json",
+        r#"{"message":"Hello, World!"}"#,
+    ),
+    (
+        "GET",
+        "/healthz",
+        "",
+        200,
+        "application/json",
+        r#"{"status":"ok"}"#,
+    ),
+    (
+        "GET",
+        "/visitors",
+        "",
+        200,
+        "application/json",
+        r#"[{"name":"bert","greeting":"Hello Bert, enjoy your treehouse.","age":null,"visits":0,"last_seen":null},{"name":"steve","greeting":"Hi Steve. Your milk is in the fridge.","age":null,"visits":0,"last_seen":null},{"name":"fred","greeting":"Wow, who invited Fred?","age":null,"visits":0,"last_seen":null}]"#,
+    ),
+    (
+        "POST",
+        "/visitors",
+        "{",
+        400,
+        "application/json",
+        r#"{"code":400,"error":"Request body deserialize error: EOF while parsing an object at line 1 column 1"}"#,
+    ),
+    (
+        "POST",
+        "/visitors",
+        r#"{"name":"bert","greeting":"Hi"}"#,
+        409,
+        "application/json",
+        r#"{"code":409,"error":"bert is already on the list"}"#,
+    ),
+    (
+        "POST",
+        "/arrivals",
+        r#"{"name":""}"#,
+        400,
+        "application/json",
+        r#"{"code":400,"error":"Arrivals need a name"}"#,
+    ),
+    (
+        "POST",
+        "/arrivals",
+        r#"{"name":"steve"}"#,
+        200,
+        "application/json",
+        r#"{"name":"steve","admitted":true,"greeting":"Hi Steve. Your milk is in the fridge.","added":false,"reasons":[]}"#,
+    ),
+    (
+        "POST",
+        "/advent/6/1",
+        "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n",
+        200,
+        "application/json",
+        r#"{"day":6,"part":1,"answer":"11"}"#,
+    ),
+    (
+        "POST",
+        "/advent/11/1",
+        "",
+        404,
+        "application/json",
+        r#"{"code":404,"error":"No solver for day 11 part 1"}"#,
+    ),
+    (
+        "PUT",
+        "/",
+        "",
+        405,
+        "application/json",
+        r#"{"code":405,"error":"Method not allowed"}"#,
+    ),
+    (
+        "GET",
+        "/files/index.html",
+        "",
+        404,
+        "application/json",
+        r#"{"code":404,"error":"Not found"}"#,
+    ),
+    (
+        "GET",
+        "/nowhere",
+        "",
+        404,
+        "application/json",
+        r#"{"code":404,"error":"Not found"}"#,
+    ),
+];
+
+#[tokio::test]
+async fn test_route_table() {
+    for (method, path, body, status, content_type, expected) in ROUTE_TABLE.iter() {
+        let res = warp::test::request()
+            .method(method)
+            .path(path)
+            .header("content-type", "application/json")
+            .body(*body)
+            .reply(&test_routes())
+            .await;
+
+        assert_eq!(*status, res.status(), "{} {}", method, path);
+        assert_eq!(
+            *content_type,
+            res.headers()["content-type"],
+            "{} {}",
+            method,
+            path
+        );
+        assert_eq!(
+            *expected,
+            String::from_utf8_lossy(res.body()),
+            "{} {}",
+            method,
+            path
+        );
+    }
+}
+
+// test_served_over_tcp runs the route table against a real server, so hyper and tokio are
+// checked too, not just the filters.
+#[tokio::test]
+async fn test_served_over_tcp() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    let (addr, server) = warp::serve(test_routes()).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    for (method, path, body, status, content_type, expected) in ROUTE_TABLE.iter() {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            addr,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, response_body) = response.split_at(response.find("\r\n\r\n").unwrap() + 4);
+        assert!(
+            head.starts_with(&format!("HTTP/1.1 {} ", status)),
+            "{} {}: {}",
+            method,
+            path,
+            head
+        );
+        assert!(
+            head.contains(&format!("content-type: {}\r\n", content_type)),
+            "{} {}: {}",
+            method,
+            path,
+            head
+        );
+        assert_eq!(*expected, response_body, "{} {}", method, path);
+    }
+}
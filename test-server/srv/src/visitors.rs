@@ -0,0 +1,264 @@
+use crate::errors::ApiError;
+use crate::routes::json_body;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use treehouse::door::Door;
+use treehouse::visitor::Visitor;
+use warp::http::StatusCode;
+use warp::Filter;
+
+// Treehouse is the door shared between every request, plus where to save the visitor list after
+// it changes. Without a path nothing is saved, which is what the tests want.
+pub struct Treehouse {
+    pub door: Door,
+    pub path: Option<PathBuf>,
+}
+
+pub type SharedTreehouse = Arc<Mutex<Treehouse>>;
+
+impl Treehouse {
+    pub fn new(door: Door, path: Option<PathBuf>) -> SharedTreehouse {
+        Arc::new(Mutex::new(Treehouse { door, path }))
+    }
+
+    fn save(&self) {
+        if let Some(path) = &self.path {
+            if let Err(err) = self.door.visitor_list.save(path) {
+                eprintln!("Problem saving visitors: {}", err);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewVisitor {
+    pub name: String,
+    pub greeting: String,
+    pub age: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ArrivalRequest {
+    pub name: String,
+    pub age: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArrivalResponse {
+    pub name: String,
+    pub admitted: bool,
+    pub greeting: Option<String>,
+    pub added: bool,
+    // Why the door said no. Empty when the visitor was let in.
+    pub reasons: Vec<String>,
+}
+
+// routes serves the treehouse over HTTP:
+//   GET  /visitors           everyone on the visitor list
+//   POST /visitors           add someone to the list
+//   POST /arrivals {"name"}  knock on the door, returning the greeting or the refusal
+pub fn routes(
+    treehouse: SharedTreehouse,
+    max_json_body: u64,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let list = warp::path!("visitors")
+        .and(warp::get())
+        .and(with_treehouse(treehouse.clone()))
+        .and_then(list_visitors);
+
+    let add = warp::path!("visitors")
+        .and(warp::post())
+        .and(json_body(max_json_body))
+        .and(with_treehouse(treehouse.clone()))
+        .and_then(add_visitor);
+
+    let arrive = warp::path!("arrivals")
+        .and(warp::post())
+        .and(json_body(max_json_body))
+        .and(with_treehouse(treehouse))
+        .and_then(arrive);
+
+    list.or(add).or(arrive)
+}
+
+fn with_treehouse(
+    treehouse: SharedTreehouse,
+) -> impl Filter<Extract = (SharedTreehouse,), Error = Infallible> + Clone {
+    warp::any().map(move || treehouse.clone())
+}
+
+async fn list_visitors(treehouse: SharedTreehouse) -> Result<impl warp::Reply, Infallible> {
+    let treehouse = treehouse.lock().await;
+    Ok(warp::reply::json(&treehouse.door.visitor_list.visitors))
+}
+
+async fn add_visitor(
+    new_visitor: NewVisitor,
+    treehouse: SharedTreehouse,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if new_visitor.name.trim().is_empty() {
+        let error = ApiError::BadRequest("Visitors need a name".to_string());
+        return Err(warp::reject::custom(error));
+    }
+
+    let mut treehouse = treehouse.lock().await;
+    if treehouse
+        .door
+        .visitor_list
+        .find(&new_visitor.name)
+        .is_some()
+    {
+        let error = ApiError::Conflict(format!("{} is already on the list", new_visitor.name));
+        return Err(warp::reject::custom(error));
+    }
+
+    let mut visitor = Visitor::new(&new_visitor.name, &new_visitor.greeting);
+    visitor.age = new_visitor.age;
+    treehouse.door.visitor_list.add(visitor.clone());
+    treehouse.save();
+
+    Ok(warp::reply::with_status(
+        warp::reply::json(&visitor),
+        StatusCode::CREATED,
+    ))
+}
+
+async fn arrive(
+    arrival: ArrivalRequest,
+    treehouse: SharedTreehouse,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if arrival.name.trim().is_empty() {
+        let error = ApiError::BadRequest("Arrivals need a name".to_string());
+        return Err(warp::reject::custom(error));
+    }
+
+    let mut treehouse = treehouse.lock().await;
+    let admission = treehouse
+        .door
+        .arrive(&arrival.name, arrival.age, Local::now());
+    if admission.decision.admitted() {
+        treehouse.save();
+    }
+
+    let response = ArrivalResponse {
+        admitted: admission.decision.admitted(),
+        reasons: admission
+            .decision
+            .failures()
+            .iter()
+            .map(|reason| reason.to_string())
+            .collect(),
+        name: admission.name,
+        greeting: admission.greeting,
+        added: admission.added,
+    };
+    Ok(warp::reply::json(&response))
+}
+
+#[cfg(test)]
+fn test_treehouse() -> SharedTreehouse {
+    use treehouse::policy::Policy;
+    use treehouse::visitor_list::VisitorList;
+
+    let policy = Policy::parse(
+        r#"
+[[rules]]
+rule = "banned_names"
+names = ["fred"]
+"#,
+    )
+    .unwrap();
+    Treehouse::new(Door::new(VisitorList::default_list(), policy), None)
+}
+
+#[cfg(test)]
+fn test_routes(
+    treehouse: SharedTreehouse,
+) -> impl Filter<Extract = impl warp::Reply, Error = Infallible> + Clone {
+    let max_json_body = crate::limits::Limits::default().max_json_body;
+    routes(treehouse, max_json_body).recover(crate::errors::handle_rejection)
+}
+
+#[tokio::test]
+async fn test_list_visitors() {
+    let res = warp::test::request()
+        .method("GET")
+        .path("/visitors")
+        .reply(&test_routes(test_treehouse()))
+        .await;
+
+    assert_eq!(StatusCode::OK, res.status());
+    let visitors: Vec<Visitor> = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(3, visitors.len());
+}
+
+#[tokio::test]
+async fn test_add_visitor() {
+    let treehouse = test_treehouse();
+    let res = warp::test::request()
+        .method("POST")
+        .path("/visitors")
+        .json(&serde_json::json!({"name": "Alice", "greeting": "Hi Alice"}))
+        .reply(&test_routes(treehouse.clone()))
+        .await;
+    assert_eq!(StatusCode::CREATED, res.status());
+    assert!(treehouse
+        .lock()
+        .await
+        .door
+        .visitor_list
+        .find("alice")
+        .is_some());
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/visitors")
+        .json(&serde_json::json!({"name": "alice", "greeting": "Hi again"}))
+        .reply(&test_routes(treehouse))
+        .await;
+    assert_eq!(StatusCode::CONFLICT, res.status());
+    assert_eq!(
+        r#"{"code":409,"error":"alice is already on the list"}"#,
+        res.body()
+    );
+}
+
+#[tokio::test]
+async fn test_arrivals() {
+    let treehouse = test_treehouse();
+    let res = warp::test::request()
+        .method("POST")
+        .path("/arrivals")
+        .json(&serde_json::json!({"name": "steve"}))
+        .reply(&test_routes(treehouse.clone()))
+        .await;
+    assert_eq!(StatusCode::OK, res.status());
+    let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(true, body["admitted"]);
+    assert_eq!("Hi Steve. Your milk is in the fridge.", body["greeting"]);
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/arrivals")
+        .json(&serde_json::json!({"name": "fred"}))
+        .reply(&test_routes(treehouse))
+        .await;
+    let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(false, body["admitted"]);
+    assert_eq!(serde_json::json!(["fred is banned"]), body["reasons"]);
+}
+
+#[tokio::test]
+async fn test_arrivals_need_a_name() {
+    let res = warp::test::request()
+        .method("POST")
+        .path("/arrivals")
+        .json(&serde_json::json!({"name": " "}))
+        .reply(&test_routes(test_treehouse()))
+        .await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.status());
+}